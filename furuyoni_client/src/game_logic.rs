@@ -10,7 +10,7 @@ use furuyoni_lib::net::message_sender::MessageSendError;
 use furuyoni_lib::net::MessageRecvError;
 use furuyoni_lib::rules::events::GameEvent;
 use furuyoni_lib::rules::player_actions::MainPhaseAction;
-use furuyoni_lib::rules::GameResult;
+use furuyoni_lib::rules::{ GameResult, PlayerPos };
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -51,9 +51,9 @@ pub(crate) async fn run_game(
         }
     };
 
-    // initialize in the main thread.
+    // initialize in the main thread. The board still shows the game if the player reconnects.
     ctx.run_on_main_thread(move |ctx| {
-        board_plugin::initialize_board(ctx.world, state, me);
+        board_plugin::reset_board(ctx.world, state, me);
     }).await;
 
     // notify that the client has successfully started the game.
@@ -65,20 +65,25 @@ pub(crate) async fn run_game(
             GameToPlayerRequest::NotifyEvent(event) => {
                 board_plugin::apply_event(&ctx, event, me).await?;
 
-                if let GameEvent::GameEnd { result } = event {
-                    break result;
+                if let GameEvent::GameEnd(outcome) = event {
+                    break outcome.result;
                 }
             }
             GameToPlayerRequest::RequestMainPhaseAction(req) => {
+                let playable_cards = Arc::new(req.playable_cards);
                 let allowed_costs = Arc::new(req.available_basic_action_costs);
                 let allowed_actions = Arc::new(req.performable_basic_actions);
 
                 let action = loop {
                     let picked_main_action = picker::pick_main_phase_action(
                         ctx.clone(),
+                        playable_cards.clone(),
                         allowed_costs.clone()
                     ).await;
                     match picked_main_action {
+                        PickMainPhaseActionResult::PlayCard(selector) => {
+                            break MainPhaseAction::PlayCard(selector);
+                        }
                         PickMainPhaseActionResult::PayBasicActionCost(cost) => {
                             let picked_basic_action = picker::pick_basic_action(
                                 &ctx,
@@ -103,7 +108,50 @@ pub(crate) async fn run_game(
                     }
                 };
 
-                responder.send(PlayerToGameResponse::MainPhaseAction(action))?;
+                respond(&ctx, &responder, PlayerToGameResponse::MainPhaseAction(action)).await?;
+            }
+            GameToPlayerRequest::RequestDamageTarget(req) => {
+                let target = picker::pick_damage_target(
+                    &ctx,
+                    Arc::new(req.available_targets)
+                ).await;
+
+                respond(&ctx, &responder, PlayerToGameResponse::DamageTarget(target)).await?;
+            }
+            GameToPlayerRequest::RequestReaction(req) => {
+                let reaction = picker::pick_reaction(
+                    &ctx,
+                    Arc::new(req.playable_reactions)
+                ).await;
+
+                respond(&ctx, &responder, PlayerToGameResponse::Reaction(reaction)).await?;
+            }
+            GameToPlayerRequest::RequestEffectChoice(req) => {
+                let choice = picker::pick_option(&ctx, req.options).await;
+                respond(&ctx, &responder, PlayerToGameResponse::EffectChoice(choice)).await?;
+            }
+            GameToPlayerRequest::RequestDiscard(req) => {
+                let selected = picker::pick_hand_cards(&ctx, Some(req.amount)).await;
+                respond(&ctx, &responder, PlayerToGameResponse::Discard(selected)).await?;
+            }
+            GameToPlayerRequest::RequestMulligan => {
+                // Any number of cards can be put back, including none.
+                let selected = picker::pick_hand_cards(&ctx, None).await;
+                respond(&ctx, &responder, PlayerToGameResponse::Mulligan(selected)).await?;
+            }
+            GameToPlayerRequest::RequestReshuffle => {
+                let options = vec![
+                    "Reshuffle (1 Life damage)".to_string(),
+                    "Don't reshuffle".to_string()
+                ];
+                let reshuffle = picker::pick_option(&ctx, options).await == 0;
+                respond(&ctx, &responder, PlayerToGameResponse::Reshuffle(reshuffle)).await?;
+            }
+            GameToPlayerRequest::NotifyRejection(reason) => {
+                warn!("Response rejected: {reason}");
+            }
+            GameToPlayerRequest::NotifyTimeLeft(time_left) => {
+                board_plugin::start_decision_clock(&ctx, time_left).await;
             }
             GameToPlayerRequest::CheckGameState(state) => {
                 board_plugin::check_game_state(&ctx, state).await;
@@ -130,3 +178,61 @@ pub(crate) async fn run_game(
 
     Ok(())
 }
+
+/// Send back the response to a request, and stop the clock of the decision.
+async fn respond(
+    ctx: &TaskContext,
+    responder: &PlayerToGameResponder,
+    response: PlayerToGameResponse
+) -> Result<(), GameLogicError> {
+    board_plugin::stop_decision_clock(ctx).await;
+    responder.send(response)?;
+
+    Ok(())
+}
+
+/// Show a game played by others. The spectator sees the board from P1's side, and is never asked
+/// for a decision.
+pub(crate) async fn spectate_game(
+    mut responder: PlayerToGameResponder,
+    ctx: TaskContext
+) -> Result<(), GameLogicError> {
+    let state = match responder.receive().await? {
+        GameToPlayerRequest::InitializeGameState(state) => state,
+        r => {
+            return Err(InvalidRequest(r));
+        }
+    };
+
+    let viewed_from = PlayerPos::P1;
+    ctx.run_on_main_thread(move |ctx| {
+        board_plugin::initialize_board(ctx.world, state, viewed_from);
+    }).await;
+
+    let result = loop {
+        match responder.receive().await? {
+            GameToPlayerRequest::NotifyEvent(event) => {
+                board_plugin::apply_event(&ctx, event, viewed_from).await?;
+
+                if let GameEvent::GameEnd(outcome) = event {
+                    break outcome.result;
+                }
+            }
+            r => {
+                return Err(InvalidRequest(r));
+            }
+        }
+    };
+
+    info!("Game ended.");
+    match result {
+        GameResult::Draw => {
+            info!("Draw.");
+        }
+        GameResult::Winner(p) => {
+            info!("{p:?} won.");
+        }
+    }
+
+    Ok(())
+}
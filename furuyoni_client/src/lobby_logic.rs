@@ -0,0 +1,184 @@
+use crate::lobby_logic::LobbyLogicError::InvalidResponse;
+use bevy::prelude::*;
+use furuyoni_lib::net::frames::{
+    Credentials,
+    LobbyToPlayerNotification,
+    LobbyToPlayerRequest,
+    LobbyToPlayerRequestData,
+    LobbyToPlayerResponse,
+    PlayerIdentity,
+    PlayerToLobbyCreateRoom,
+    PlayerToLobbyHandshake,
+    PlayerToLobbyRequest,
+    PlayerToLobbyResponse,
+    PlayerToLobbySpectate,
+    PlayerToLobbyTryEnterRoom,
+    SessionId,
+    PROTOCOL_VERSION,
+};
+use furuyoni_lib::net::message_channel::MessageChannel;
+use furuyoni_lib::net::message_sender::MessageSendError;
+use furuyoni_lib::net::MessageRecvError;
+use std::time::Duration;
+use thiserror::Error;
+
+type PlayerToLobbyRequester = MessageChannel<PlayerToLobbyRequest, LobbyToPlayerResponse>;
+type PlayerToLobbyResponder = MessageChannel<PlayerToLobbyResponse, LobbyToPlayerRequest>;
+
+#[derive(Debug, Error)]
+pub(crate) enum LobbyLogicError {
+    #[error("Failed to receive a response from the server: {0}")] ResponseReceiveFailed(
+        #[from] MessageRecvError,
+    ),
+    #[error("Failed to send a request to the server: {0}")] RequestSendFailed(
+        #[from] MessageSendError,
+    ),
+    #[error("Received an invalid response from the server: {0:?}")] InvalidResponse(
+        LobbyToPlayerResponse,
+    ),
+    #[error("The request has been refused: {0}")] Refused(String),
+    #[error("There is no game to watch.")] NoGameToWatch,
+    #[error("The game has been cancelled: {0}")] GameCancelled(String),
+}
+
+async fn request(
+    requester: &mut PlayerToLobbyRequester,
+    request: PlayerToLobbyRequest
+) -> Result<LobbyToPlayerResponse, LobbyLogicError> {
+    requester.send(request)?;
+
+    match requester.receive().await? {
+        LobbyToPlayerResponse::Refused(reason) => Err(LobbyLogicError::Refused(reason)),
+        response => Ok(response),
+    }
+}
+
+/// Start a session with the server. Should be done before any other request.
+pub(crate) async fn handshake(
+    requester: &mut PlayerToLobbyRequester,
+    credentials: Credentials
+) -> Result<(SessionId, PlayerIdentity), LobbyLogicError> {
+    let handshake = PlayerToLobbyRequest::Handshake(PlayerToLobbyHandshake {
+        protocol_version: PROTOCOL_VERSION,
+        credentials,
+    });
+
+    match request(requester, handshake).await? {
+        LobbyToPlayerResponse::SessionStarted { session_id, identity } =>
+            Ok((session_id, identity)),
+        r => Err(InvalidResponse(r)),
+    }
+}
+
+/// Take a seat in a room and get ready. The game starts once every player in the room is ready.
+// Todo: a lobby screen. Enter the first room with a free seat, or create one for now.
+pub(crate) async fn enter_room(
+    requester: &mut PlayerToLobbyRequester
+) -> Result<(), LobbyLogicError> {
+    let rooms = match request(requester, PlayerToLobbyRequest::GetRoomsList).await? {
+        LobbyToPlayerResponse::RoomsList(rooms) => rooms,
+        r => {
+            return Err(InvalidResponse(r));
+        }
+    };
+
+    let mut entered = false;
+    for room in rooms.iter().filter(|room| room.current_player_num < room.max_player_num) {
+        let enter_room = PlayerToLobbyRequest::TryEnterRoom(PlayerToLobbyTryEnterRoom {
+            room_id: room.room_id,
+        });
+        match request(requester, enter_room).await? {
+            LobbyToPlayerResponse::RoonEnterSuccess(true) => {
+                entered = true;
+                break;
+            }
+            LobbyToPlayerResponse::RoonEnterSuccess(false) => {}
+            r => {
+                return Err(InvalidResponse(r));
+            }
+        }
+    }
+
+    if !entered {
+        let create_room = PlayerToLobbyRequest::CreateRoom(PlayerToLobbyCreateRoom {
+            room_name: "Furuyoni".to_string(),
+            room_description: String::new(),
+            forfeit_on_timeout: false,
+        });
+        match request(requester, create_room).await? {
+            LobbyToPlayerResponse::RoomCreated(_) => {}
+            r => {
+                return Err(InvalidResponse(r));
+            }
+        }
+    }
+
+    match request(requester, PlayerToLobbyRequest::Ready(true)).await? {
+        LobbyToPlayerResponse::Ack => Ok(()),
+        r => Err(InvalidResponse(r)),
+    }
+}
+
+/// Wait in the matchmaking queue until the server finds a match.
+pub(crate) async fn join_matchmaking(
+    requester: &mut PlayerToLobbyRequester,
+    responder: &mut PlayerToLobbyResponder
+) -> Result<(), LobbyLogicError> {
+    match request(requester, PlayerToLobbyRequest::JoinMatchmaking).await? {
+        LobbyToPlayerResponse::Ack => {}
+        r => {
+            return Err(InvalidResponse(r));
+        }
+    }
+
+    loop {
+        match responder.receive().await? {
+            LobbyToPlayerRequest::Notify(LobbyToPlayerNotification::QueueStatus {
+                position,
+                queue_len,
+            }) => {
+                // Todo: show the queue status on the screen.
+                info!("Waiting for a match: {}/{}", position + 1, queue_len);
+            }
+            LobbyToPlayerRequest::Notify(LobbyToPlayerNotification::MatchFound) => {
+                return Ok(());
+            }
+            LobbyToPlayerRequest::Notify(LobbyToPlayerNotification::GameCancelled(reason)) => {
+                return Err(LobbyLogicError::GameCancelled(reason));
+            }
+            LobbyToPlayerRequest::RequestData(LobbyToPlayerRequestData::AreYouAlive) => {
+                responder.send(PlayerToLobbyResponse::Ack)?;
+            }
+        }
+    }
+}
+
+/// Watch a game being played. The game is streamed through the game channels afterwards.
+// Todo: a lobby screen to choose the game. Watch the most recent one for now.
+pub(crate) async fn spectate(
+    requester: &mut PlayerToLobbyRequester,
+    delay: Option<Duration>
+) -> Result<(), LobbyLogicError> {
+    let games = match request(requester, PlayerToLobbyRequest::GetGamesList).await? {
+        LobbyToPlayerResponse::GamesList(games) => games,
+        r => {
+            return Err(InvalidResponse(r));
+        }
+    };
+
+    let game = games.last().ok_or(LobbyLogicError::NoGameToWatch)?;
+    info!(
+        "Watching {} vs {}",
+        game.players.p1_data.display_name,
+        game.players.p2_data.display_name
+    );
+
+    let spectate = PlayerToLobbyRequest::Spectate(PlayerToLobbySpectate {
+        game_id: game.game_id,
+        delay,
+    });
+    match request(requester, spectate).await? {
+        LobbyToPlayerResponse::Ack => Ok(()),
+        r => Err(InvalidResponse(r)),
+    }
+}
@@ -1,11 +1,16 @@
 mod game_logic;
+mod lobby_logic;
 mod networking;
+mod replay_logic;
 mod systems;
 
 use std::f32::consts::PI;
+use std::time::Duration;
 
 use crate::game_logic::GameLogicError;
+use crate::lobby_logic::LobbyLogicError;
 use crate::networking::post_office::spawn_post_office;
+use crate::replay_logic::ReplayLogicError;
 use crate::systems::board_plugin::{
     BoardPlugin,
     CardsRelativePosition,
@@ -13,8 +18,10 @@ use crate::systems::board_plugin::{
     PlayerRelativePos,
     StateLabel,
     StateStringPicker,
+    TimeLeftLabel,
 };
 use crate::systems::picker::{ Pickable, PickerButton, PickerPlugin };
+use crate::systems::replay_controls::ReplayControlsPlugin;
 use bevy::app::AppExit;
 use bevy::color::palettes::css::GREEN;
 use bevy::prelude::*;
@@ -24,21 +31,50 @@ use bevy::DefaultPlugins;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_tokio_tasks::{ TaskContext, TokioTasksPlugin, TokioTasksRuntime };
 use bevy_tweening::TweeningPlugin;
+use furuyoni_lib::net::frames::{
+    Credentials,
+    LobbyToPlayerRequest,
+    LobbyToPlayerResponse,
+    PlayerToLobbyRequest,
+    PlayerToLobbyResponse,
+    SessionId,
+};
+use furuyoni_lib::net::message_channel::MessageChannel;
+use furuyoni_lib::rules::attack::DamageTarget;
 use furuyoni_lib::rules::player_actions::BasicAction;
-use systems::board_plugin::{ CardInspectPosition, DeckObject, HandObject, Spread };
+use systems::board_plugin::{ CardInspectPosition, CardsObject, Spread };
 use thiserror::Error;
 use tokio::net::TcpStream;
 
 #[derive(Debug, Error)]
 pub(crate) enum Error {
     #[error("Failed to connect to the server.")] ConnectionFailed(tokio::io::Error),
+    #[error("{0}")] LobbyLogicError(#[from] LobbyLogicError),
     #[error("{0}")] GameLogicError(#[from] GameLogicError),
+    #[error("{0}")] ReplayLogicError(#[from] ReplayLogicError),
+}
+
+impl Error {
+    /// Whether the connection to the server has been lost.
+    fn is_disconnection(&self) -> bool {
+        matches!(
+            self,
+            Error::ConnectionFailed(_) |
+                Error::LobbyLogicError(
+                    LobbyLogicError::ResponseReceiveFailed(_) | LobbyLogicError::RequestSendFailed(_)
+                ) |
+                Error::GameLogicError(
+                    GameLogicError::RequestReceiveFailed(_) | GameLogicError::ResponseSendFailed(_)
+                )
+        )
+    }
 }
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(PickerPlugin)
+        .add_plugins(ReplayControlsPlugin)
         .add_plugins(BoardPlugin)
         .add_plugins(TokioTasksPlugin::default())
         .add_plugins(TweeningPlugin)
@@ -63,15 +99,100 @@ pub(crate) fn spawn_logic_thread(runtime: ResMut<TokioTasksRuntime>) {
     });
 }
 
+/// How many times to try to get back into the game after losing the connection.
+const RECONNECT_ATTEMPTS: usize = 5;
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(3);
+
 async fn run_logic_thread(ctx: TaskContext) -> Result<(), Error> {
-    let socket = TcpStream::connect("127.0.0.1:4255").await.map_err(|e|
-        Error::ConnectionFailed(e)
-    )?;
+    // Show a recorded game without connecting to the server.
+    if let Some(path) = arg_value("--replay") {
+        replay_logic::run_replay(path, ctx).await?;
+        return Ok(());
+    }
+
+    // A spectator has no seat to get back to, so there is no session to resume.
+    if std::env::args().any(|arg| arg == "--spectate") {
+        return connect_and_spectate(ctx).await;
+    }
+
+    let mut session_id = None;
+    let mut attempts = 0;
+
+    loop {
+        match connect_and_play(&mut session_id, ctx.clone()).await {
+            // The server keeps the seat for a while, so resume the session to get back into the game.
+            Err(e) if e.is_disconnection() && session_id.is_some() && attempts < RECONNECT_ATTEMPTS => {
+                warn!("{e} Reconnecting...");
+                attempts += 1;
+                tokio::time::sleep(RECONNECT_INTERVAL).await;
+            }
+            result => {
+                return result;
+            }
+        }
+    }
+}
 
-    let (player_to_game_requester, player_to_game_responder, post_office_task) =
-        spawn_post_office(socket);
+/// Connect to the server and play a game. The session is resumed if there is one, which puts the
+/// player back into the game it has been playing.
+async fn connect_and_play(
+    session_id: &mut Option<SessionId>,
+    ctx: TaskContext
+) -> Result<(), Error> {
+    let socket = connect().await?;
+
+    let (
+        mut player_to_lobby_requester,
+        mut player_to_lobby_responder,
+        _player_to_game_requester,
+        player_to_game_responder,
+        post_office_task,
+    ) = spawn_post_office(socket);
+
+    let lobby_result = match *session_id {
+        Some(resumed) => {
+            let credentials = Credentials::Resume(resumed);
+            lobby_logic::handshake(&mut player_to_lobby_requester, credentials).await.map(|_| ())
+        }
+        None => {
+            let started = enter_game(
+                &mut player_to_lobby_requester,
+                &mut player_to_lobby_responder
+            ).await;
+            started.map(|started| {
+                *session_id = Some(started);
+            })
+        }
+    };
+
+    let ret = match lobby_result {
+        Ok(()) => game_logic::run_game(player_to_game_responder, ctx).await.map_err(Error::from),
+        Err(e) => Err(e.into()),
+    };
+
+    post_office_task.abort();
+
+    ret?;
+    Ok(())
+}
 
-    let ret = game_logic::run_game(player_to_game_responder, ctx).await;
+/// Connect to the server and watch a game without playing.
+async fn connect_and_spectate(ctx: TaskContext) -> Result<(), Error> {
+    let socket = connect().await?;
+
+    let (
+        mut player_to_lobby_requester,
+        _player_to_lobby_responder,
+        _player_to_game_requester,
+        player_to_game_responder,
+        post_office_task,
+    ) = spawn_post_office(socket);
+
+    let ret = match enter_spectating(&mut player_to_lobby_requester).await {
+        Ok(()) =>
+            game_logic::spectate_game(player_to_game_responder, ctx).await.map_err(Error::from),
+        Err(e) => Err(e.into()),
+    };
 
     post_office_task.abort();
 
@@ -79,6 +200,61 @@ async fn run_logic_thread(ctx: TaskContext) -> Result<(), Error> {
     Ok(())
 }
 
+async fn connect() -> Result<TcpStream, Error> {
+    TcpStream::connect("127.0.0.1:4255").await.map_err(|e| Error::ConnectionFailed(e))
+}
+
+/// Start a session, then wait in the lobby until a game starts.
+async fn enter_game(
+    requester: &mut MessageChannel<PlayerToLobbyRequest, LobbyToPlayerResponse>,
+    responder: &mut MessageChannel<PlayerToLobbyResponse, LobbyToPlayerRequest>
+) -> Result<SessionId, LobbyLogicError> {
+    let session_id = start_session(requester).await?;
+
+    // Todo: a lobby screen to choose between rooms and matchmaking.
+    if std::env::args().any(|arg| arg == "--room") {
+        lobby_logic::enter_room(requester).await?;
+    } else {
+        lobby_logic::join_matchmaking(requester, responder).await?;
+    }
+
+    Ok(session_id)
+}
+
+/// Start a session, then ask to watch a game. `--delay SECONDS` shows the game that far behind.
+async fn enter_spectating(
+    requester: &mut MessageChannel<PlayerToLobbyRequest, LobbyToPlayerResponse>
+) -> Result<(), LobbyLogicError> {
+    start_session(requester).await?;
+
+    let delay = arg_value("--delay")
+        .and_then(|seconds| seconds.parse().ok())
+        .map(Duration::from_secs);
+
+    lobby_logic::spectate(requester, delay).await
+}
+
+/// Start a session as a guest named by `--name NAME`.
+async fn start_session(
+    requester: &mut MessageChannel<PlayerToLobbyRequest, LobbyToPlayerResponse>
+) -> Result<SessionId, LobbyLogicError> {
+    let display_name = arg_value("--name").unwrap_or_default();
+
+    let (session_id, identity) = lobby_logic::handshake(
+        requester,
+        Credentials::DisplayName(display_name)
+    ).await?;
+    info!("Session started: {:?} as {}", session_id, identity.display_name);
+
+    Ok(session_id)
+}
+
+/// Return the command line argument that follows the given one.
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args();
+    args.position(|arg| arg == name).and_then(|_| args.next())
+}
+
 fn _load_scene(asset_server: Res<AssetServer>, mut scene_spawner: ResMut<SceneSpawner>) {
     let ff: Handle<Font> = asset_server.load("fonts/Fira_Sans/FiraSans-Regular.ttf");
     std::mem::forget(ff);
@@ -195,6 +371,29 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut windows: Qu
         StateStringPicker::PetalsCount(PetalsRelativePosition::Dust)
     );
 
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::new("Time: ", TextStyle {
+                font: font.clone(),
+                font_size: 50.0,
+                ..default()
+            }),
+            TextSection::new("", TextStyle {
+                font: font.clone(),
+                font_size: 50.0,
+                color: GREEN.into(),
+            }),
+        ])
+            .with_text_justify(JustifyText::Left)
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(20.0 + LH * 1.0),
+                left: Val::Percent(85.0),
+                ..default()
+            }),
+        TimeLeftLabel::new(1),
+    ));
+
     commands
         .spawn((
             ButtonBundle {
@@ -290,7 +489,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut windows: Qu
         },
     ));
 
-    let mut spawn_ba_button = |bottom, right, str: &str, action| {
+    let mut spawn_picker_button = |bottom, right, str: &str, pickable| {
         commands
             .spawn((
                 ButtonBundle {
@@ -311,9 +510,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut windows: Qu
                     background_color: Color::srgb(0.2, 0.5, 0.3).into(),
                     ..default()
                 },
-                PickerButton {
-                    pickable: Pickable::BasicAction(action),
-                },
+                PickerButton { pickable },
             ))
             .with_children(|parent| {
                 parent.spawn(
@@ -326,50 +523,96 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut windows: Qu
             });
     };
 
-    spawn_ba_button(20.0, 46.0, "Forward", BasicAction::MoveForward);
-    spawn_ba_button(10.0, 46.0, "Backward", BasicAction::MoveBackward);
-    spawn_ba_button(20.0, 33.0, "Focus", BasicAction::Focus);
-    spawn_ba_button(10.0, 33.0, "Recover", BasicAction::Recover);
-
-    // spawn deck position indicators.
-    const DECK_CARDS_SCALE: Vec3 = Vec3::splat(0.7);
-    commands.spawn((
-        Name::new("Deck(Opponent)"),
-        TransformBundle::from_transform(
-            Transform::from_xyz(800.0, -80.0, 0.0).with_scale(DECK_CARDS_SCALE)
-        ),
-        DeckObject::new(PlayerRelativePos::Me),
-    ));
+    spawn_picker_button(20.0, 46.0, "Forward", Pickable::BasicAction(BasicAction::MoveForward));
+    spawn_picker_button(10.0, 46.0, "Backward", Pickable::BasicAction(BasicAction::MoveBackward));
+    spawn_picker_button(20.0, 33.0, "Focus", Pickable::BasicAction(BasicAction::Focus));
+    spawn_picker_button(10.0, 33.0, "Recover", Pickable::BasicAction(BasicAction::Recover));
+
+    spawn_picker_button(20.0, 20.0, "Pass", Pickable::Pass);
+    spawn_picker_button(20.0, 20.0, "Confirm", Pickable::Confirm);
+
+    spawn_picker_button(20.0, 59.0, "Aura", Pickable::DamageTarget(DamageTarget::Aura));
+    spawn_picker_button(10.0, 59.0, "Life", Pickable::DamageTarget(DamageTarget::Life));
+
+    // spawn the places of the cards. The opponent's side is the point reflection of mine.
+    const CARDS_SCALE: Vec3 = Vec3::splat(0.7);
+    let mut spawn_cards_object = |
+        name: &str,
+        position: fn(PlayerRelativePos) -> CardsRelativePosition,
+        translation: Vec3,
+        spread: Option<Spread>
+    | {
+        let sides = [
+            (PlayerRelativePos::Me, Transform::from_translation(translation)),
+            (
+                PlayerRelativePos::Opponent,
+                Transform::from_xyz(-translation.x, -translation.y, translation.z).with_rotation(
+                    Quat::from_rotation_z(PI)
+                ),
+            ),
+        ];
+        for (player, transform) in sides {
+            let mut cards_object = commands.spawn((
+                Name::new(format!("{name}({player:?})")),
+                SpatialBundle::from_transform(transform.with_scale(CARDS_SCALE)),
+                CardsObject::new(position(player)),
+            ));
+            // The cards in the deck aren't laid out.
+            if let Some(spread) = &spread {
+                cards_object.insert(spread.clone());
+            }
+        }
+    };
 
-    commands.spawn((
-        Name::new("Deck(Opponent)"),
-        TransformBundle::from_transform(
-            Transform::from_xyz(-800.0, 80.0, 0.0)
-                .with_rotation(Quat::from_rotation_z(PI))
-                .with_scale(DECK_CARDS_SCALE)
-        ),
-        DeckObject::new(PlayerRelativePos::Opponent),
-    ));
+    spawn_cards_object(
+        "Deck",
+        CardsRelativePosition::Deck,
+        Vec3::new(800.0, -80.0, 0.0),
+        None
+    );
+    spawn_cards_object(
+        "Hand",
+        CardsRelativePosition::Hand,
+        Vec3::new(0.0, -450.0, 200.0),
+        Some(Spread::new(600.0, 7))
+    );
+    spawn_cards_object(
+        "Playing",
+        CardsRelativePosition::Playing,
+        Vec3::new(0.0, -180.0, 100.0),
+        Some(Spread::new(300.0, 3))
+    );
+    spawn_cards_object(
+        "Enhancements",
+        CardsRelativePosition::Enhancements,
+        Vec3::new(-450.0, -200.0, 0.0),
+        Some(Spread::new(300.0, 3))
+    );
+    spawn_cards_object(
+        "Played",
+        CardsRelativePosition::Played,
+        Vec3::new(420.0, -260.0, 0.0),
+        Some(Spread::new(150.0, 5))
+    );
+    spawn_cards_object(
+        "Discards",
+        CardsRelativePosition::Discards,
+        Vec3::new(620.0, -260.0, 0.0),
+        Some(Spread::new(150.0, 5))
+    );
 
-    // spawn card hands.
-    commands.spawn((
-        Name::new("Hand(Me)"),
-        SpatialBundle::from_transform(
-            Transform::from_xyz(0.0, -450.0, 200.0).with_scale(DECK_CARDS_SCALE)
-        ),
-        HandObject::new(PlayerRelativePos::Me),
-        Spread::new(600.0, 7),
-    ));
-    commands.spawn((
-        Name::new("Hand(Opponent)"),
-        SpatialBundle::from_transform(
-            Transform::from_xyz(0.0, 450.0, 0.0)
-                .with_rotation(Quat::from_rotation_z(PI))
-                .with_scale(DECK_CARDS_SCALE)
-        ),
-        HandObject::new(PlayerRelativePos::Opponent),
-        Spread::new(600.0, 7),
-    ));
+    spawn_cards_object(
+        "Specials",
+        CardsRelativePosition::Specials,
+        Vec3::new(-780.0, -420.0, 0.0),
+        Some(Spread::new(200.0, 3))
+    );
+    spawn_cards_object(
+        "UsedSpecials",
+        CardsRelativePosition::UsedSpecials,
+        Vec3::new(-780.0, -200.0, 0.0),
+        Some(Spread::new(200.0, 3))
+    );
 
     const INSPECTOR_SCALE: Vec3 = Vec3::splat(2.0);
 
@@ -1,8 +1,9 @@
 use crate::networking::{ClientConnectionReader, ClientConnectionWriter};
 use furuyoni_lib::net::frames::{
     ClientMessageFrame, GameToPlayerMessage, GameToPlayerRequest, GameToPlayerResponse,
-    LobbyToPlayerMessage, PlayerToGameMessage, PlayerToGameRequest, PlayerToGameResponse,
-    ServerMessageFrame,
+    LobbyToPlayerMessage, LobbyToPlayerRequest, LobbyToPlayerResponse, PlayerToGameMessage,
+    PlayerToGameRequest, PlayerToGameResponse, PlayerToLobbyMessage, PlayerToLobbyRequest,
+    PlayerToLobbyResponse, ServerMessageFrame,
 };
 use furuyoni_lib::net::message_channel::MessageChannel;
 use furuyoni_lib::net::message_sender::IntoMessageMap;
@@ -23,6 +24,8 @@ pub enum ReceivePostsError {
 pub fn spawn_post_office(
     stream: TcpStream,
 ) -> (
+    MessageChannel<PlayerToLobbyRequest, LobbyToPlayerResponse>,
+    MessageChannel<PlayerToLobbyResponse, LobbyToPlayerRequest>,
     MessageChannel<PlayerToGameRequest, GameToPlayerResponse>,
     MessageChannel<PlayerToGameResponse, GameToPlayerRequest>,
     JoinHandle<()>,
@@ -34,18 +37,34 @@ pub fn spawn_post_office(
 
     let (game_to_player_request_tx, game_to_player_request_rx) = mpsc::channel(20);
     let (game_to_player_response_tx, game_to_player_response_rx) = mpsc::channel(20);
+    let (lobby_to_player_request_tx, lobby_to_player_request_rx) = mpsc::channel(20);
+    let (lobby_to_player_response_tx, lobby_to_player_response_rx) = mpsc::channel(20);
 
     let (client_message_tx, client_message_rx) = mpsc::channel(20);
 
     let post_office_joinhandle = tokio::spawn(async {
         tokio::select!(
-            res = tokio::spawn(receive_posts(reader, game_to_player_request_tx, game_to_player_response_tx)) =>
+            res = tokio::spawn(receive_posts(reader, game_to_player_request_tx, game_to_player_response_tx, lobby_to_player_request_tx, lobby_to_player_response_tx)) =>
                 println!("receive_posts has ended with result: {:?}", res),
             res = tokio::spawn(handle_send_requests(client_message_rx, writer)) =>
                 println!("handle_send_request has ended with result: {:?}", res),
         );
     });
 
+    let player_to_lobby_request_sender = client_message_tx.clone().with_map(|request| {
+        ClientMessageFrame::PlayerToLobbyMessage(PlayerToLobbyMessage::Request(request))
+    });
+
+    let player_to_lobby_requester =
+        MessageChannel::new(player_to_lobby_request_sender, lobby_to_player_response_rx);
+
+    let player_to_lobby_response_sender = client_message_tx
+        .clone()
+        .with_map(|r| ClientMessageFrame::PlayerToLobbyMessage(PlayerToLobbyMessage::Response(r)));
+
+    let player_to_lobby_responder =
+        MessageChannel::new(player_to_lobby_response_sender, lobby_to_player_request_rx);
+
     let player_to_game_request_sender = client_message_tx.clone().with_map(|request| {
         ClientMessageFrame::PlayerToGameMessage(PlayerToGameMessage::Request(request))
     });
@@ -61,6 +80,8 @@ pub fn spawn_post_office(
         MessageChannel::new(player_to_game_response_sender, game_to_player_request_rx);
 
     return (
+        player_to_lobby_requester,
+        player_to_lobby_responder,
         player_to_game_requester,
         player_to_game_responder,
         post_office_joinhandle,
@@ -71,6 +92,8 @@ async fn receive_posts<T: AsyncRead + Unpin>(
     mut reader: ClientConnectionReader<T>,
     game_request_tx: mpsc::Sender<GameToPlayerRequest>,
     game_response_tx: mpsc::Sender<GameToPlayerResponse>,
+    lobby_request_tx: mpsc::Sender<LobbyToPlayerRequest>,
+    lobby_response_tx: mpsc::Sender<LobbyToPlayerResponse>,
 ) -> Result<(), ReceivePostsError> {
     loop {
         match reader.read_frame().await {
@@ -93,10 +116,14 @@ async fn receive_posts<T: AsyncRead + Unpin>(
                 },
                 ServerMessageFrame::LobbyMessage(msg) => match msg {
                     LobbyToPlayerMessage::Request(req) => {
-                        todo!()
+                        lobby_request_tx
+                            .try_send(req)
+                            .map_err(|_| ReceivePostsError::ChannelSendError)?;
                     }
-                    LobbyToPlayerMessage::Response(res) => {
-                        todo!()
+                    LobbyToPlayerMessage::Response(resp) => {
+                        lobby_response_tx
+                            .try_send(resp)
+                            .map_err(|_| ReceivePostsError::ChannelSendError)?;
                     }
                 },
             },
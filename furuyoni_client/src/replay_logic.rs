@@ -0,0 +1,103 @@
+use crate::systems::board_plugin;
+use crate::systems::board_plugin::BoardError;
+use crate::systems::replay_controls::{ self, ReplayCommand };
+use bevy::prelude::*;
+use bevy_tokio_tasks::*;
+use furuyoni_lib::replays::{ Replay, ReplayError, ReplayPlayer };
+use furuyoni_lib::rules::{ ObservePosition, PlayerPos };
+use std::time::Duration;
+use thiserror::Error;
+
+/// The time between two events at the normal speed.
+const EVENT_INTERVAL: Duration = Duration::from_millis(500);
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 8.0;
+
+#[derive(Debug, Error)]
+pub(crate) enum ReplayLogicError {
+    #[error("Failed to read the replay file: {0}")] ReadFailed(#[from] std::io::Error),
+    #[error("Replay error: {0}")] ReplayError(#[from] ReplayError),
+    #[error("Board error :{0}")] BoardError(#[from] BoardError),
+}
+
+/// Show a recorded game on the board, controlled with the keyboard.
+pub(crate) async fn run_replay(path: String, ctx: TaskContext) -> Result<(), ReplayLogicError> {
+    let replay = Replay::from_json(&tokio::fs::read_to_string(path).await?)?;
+    info!(
+        "Replaying {} vs {}",
+        replay.players.p1_data.display_name,
+        replay.players.p2_data.display_name
+    );
+
+    let mut player = ReplayPlayer::new(replay);
+    let mut commands = replay_controls::listen_replay_commands(&ctx).await;
+
+    let mut viewed_from = ObservePosition::RelativeTo(PlayerPos::P1);
+    let mut playing = true;
+    let mut speed = 1.0;
+
+    reset_board(&ctx, &player, viewed_from).await;
+
+    loop {
+        // Wait for the next event while playing, or for a command.
+        let command = if playing && !player.is_at_end() {
+            let interval = EVENT_INTERVAL.div_f32(speed);
+            match tokio::time::timeout(interval, commands.recv()).await {
+                Ok(command) => command,
+                Err(_) => Some(ReplayCommand::StepForward),
+            }
+        } else {
+            commands.recv().await
+        };
+        let Some(command) = command else {
+            return Ok(());
+        };
+
+        match command {
+            ReplayCommand::TogglePause => {
+                playing = !playing;
+            }
+            ReplayCommand::StepForward => {
+                if let Some(event) = player.step_forward(viewed_from)? {
+                    board_plugin::apply_event(&ctx, event, bottom_player(viewed_from)).await?;
+                }
+            }
+            ReplayCommand::StepBack => {
+                if player.step_back()? {
+                    reset_board(&ctx, &player, viewed_from).await;
+                }
+            }
+            ReplayCommand::SpeedUp => {
+                speed = f32::min(speed * 2.0, MAX_SPEED);
+                info!("Replay speed: x{speed}");
+            }
+            ReplayCommand::SlowDown => {
+                speed = f32::max(speed / 2.0, MIN_SPEED);
+                info!("Replay speed: x{speed}");
+            }
+            ReplayCommand::SetPerspective(position) => {
+                viewed_from = position;
+                reset_board(&ctx, &player, viewed_from).await;
+            }
+        }
+    }
+}
+
+/// Lay out the cards of the current point of the replay as seen from the given position. The
+/// events that follow are animated from there, whatever zone they take a card from.
+async fn reset_board(ctx: &TaskContext, player: &ReplayPlayer, viewed_from: ObservePosition) {
+    let state = player.state_view(viewed_from);
+    let me = bottom_player(viewed_from);
+
+    ctx.run_on_main_thread(move |ctx| {
+        board_plugin::reset_board(ctx.world, state, me);
+    }).await;
+}
+
+/// The player shown at the bottom of the board.
+fn bottom_player(viewed_from: ObservePosition) -> PlayerPos {
+    match viewed_from {
+        ObservePosition::RelativeTo(player) => player,
+        ObservePosition::MasterView | ObservePosition::ByStander => PlayerPos::P1,
+    }
+}
@@ -1,2 +1,3 @@
 pub mod board_plugin;
 pub mod picker;
+pub mod replay_controls;
@@ -1,23 +1,27 @@
 use furuyoni_lib::rules::{cards::Card, states::{ InvalidGameViewUpdateError, StateView }};
 use bevy::prelude::*;
 use furuyoni_lib::rules::PlayerPos;
+use std::time::Duration;
 
 mod relative_positions;
 mod requests_handler;
 mod spread_plugin;
 mod labels_update_system;
+mod decision_clock;
 
 use spread_plugin::SpreadPlugin;
 use labels_update_system::update_labels;
+use decision_clock::update_decision_clock;
 
 pub(crate) use spread_plugin::Spread;
 pub(crate) use labels_update_system::{ StateLabel, StateStringPicker };
+pub(crate) use decision_clock::{ start_decision_clock, stop_decision_clock, TimeLeftLabel };
 pub(crate) use relative_positions::{
     CardsRelativePosition,
     PetalsRelativePosition,
     PlayerRelativePos,
 };
-pub(crate) use requests_handler::{ apply_event, check_game_state, initialize_board };
+pub(crate) use requests_handler::{ apply_event, check_game_state, initialize_board, reset_board };
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -29,9 +33,23 @@ pub(crate) enum BoardError {
 
 pub(crate) struct BoardPlugin;
 
+/// The place on the board where the cards of a position are laid out. Each card is the child of
+/// an element of the `Spread` of the entity, in the same order as in the state. The cards in the
+/// deck aren't shown, so the deck only marks where the drawn cards come from and where the
+/// returned cards go.
 #[derive(Debug, Component)]
-pub(crate) struct HandObject {
-    relative_pos: PlayerRelativePos,
+pub(crate) struct CardsObject {
+    position: CardsRelativePosition,
+}
+
+impl CardsObject {
+    pub(crate) fn new(position: CardsRelativePosition) -> Self {
+        Self { position }
+    }
+
+    pub(crate) fn position(&self) -> CardsRelativePosition {
+        self.position
+    }
 }
 
 #[derive(Debug, Component)]
@@ -58,24 +76,18 @@ impl CardObject {
 
 
 
-impl HandObject {
-    pub(crate) fn new(relative_pos: PlayerRelativePos) -> Self {
-        Self { relative_pos }
-    }
-}
-
+/// A card that is leaving the board into the deck. It's removed once it has reached the deck.
 #[derive(Debug, Component)]
-pub(crate) struct DeckObject {
-    relative_pos: PlayerRelativePos,
+pub(crate) struct LeavingCardObject {
+    timer: Timer,
 }
 
-impl DeckObject {
-    pub(crate) fn new(relative_pos: PlayerRelativePos) -> Self {
-        Self { relative_pos }
+impl LeavingCardObject {
+    pub(crate) fn new(delay: Duration) -> Self {
+        Self { timer: Timer::new(delay, TimerMode::Once) }
     }
 }
 
-
 #[derive(Debug, Component)]
 pub(crate) struct CardInspectPosition;
 
@@ -97,6 +109,19 @@ impl Plugin for BoardPlugin {
                     .run_if(resource_exists::<BoardState>)
                     .run_if(resource_exists::<SelfPlayerPos>)
             )
+            .add_systems(Update, (despawn_left_cards, update_decision_clock))
             .add_plugins(SpreadPlugin);
     }
 }
+
+fn despawn_left_cards(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cards: Query<(Entity, &mut LeavingCardObject)>
+) {
+    for (card, mut leaving) in cards.iter_mut() {
+        if leaving.timer.tick(time.delta()).finished() {
+            commands.entity(card).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use bevy_tokio_tasks::TaskContext;
+use furuyoni_lib::net::frames::TimeLeft;
+use std::time::Duration;
+
+/// The time the player has left for the decision being made. The time of the decision runs out
+/// first, then the time bank.
+#[derive(Resource)]
+pub(crate) struct DecisionClock {
+    decision: Option<Duration>,
+    bank: Option<Duration>,
+}
+
+/// Shows the time left of the `DecisionClock` in the text section of the given index.
+#[derive(Debug, Component)]
+pub(crate) struct TimeLeftLabel {
+    text_section_index: usize,
+}
+
+impl TimeLeftLabel {
+    pub(crate) fn new(text_section_index: usize) -> Self {
+        Self { text_section_index }
+    }
+}
+
+/// Start counting down the time the server has given for the upcoming decision.
+pub(crate) async fn start_decision_clock(ctx: &TaskContext, time_left: TimeLeft) {
+    ctx.run_on_main_thread(move |ctx| {
+        ctx.world.insert_resource(DecisionClock {
+            decision: time_left.decision,
+            bank: time_left.bank,
+        });
+    }).await;
+}
+
+/// Stop the clock once the decision has been made.
+pub(crate) async fn stop_decision_clock(ctx: &TaskContext) {
+    ctx.run_on_main_thread(move |ctx| {
+        ctx.world.remove_resource::<DecisionClock>();
+    }).await;
+}
+
+pub(crate) fn update_decision_clock(
+    time: Res<Time>,
+    clock: Option<ResMut<DecisionClock>>,
+    mut labels: Query<(&mut Text, &TimeLeftLabel)>
+) {
+    let value = match clock {
+        Some(mut clock) => {
+            let mut elapsed = time.delta();
+            if let Some(decision) = &mut clock.decision {
+                let spent = elapsed.min(*decision);
+                *decision -= spent;
+                elapsed -= spent;
+            }
+            if let Some(bank) = &mut clock.bank {
+                *bank = bank.saturating_sub(elapsed);
+            }
+
+            time_left_string(&clock)
+        }
+        None => String::new(),
+    };
+
+    for (mut text, label) in &mut labels {
+        text.sections[label.text_section_index].value = value.clone();
+    }
+}
+
+fn time_left_string(clock: &DecisionClock) -> String {
+    match (clock.decision, clock.bank) {
+        (None, None) => "No limit".to_string(),
+        (Some(decision), None) => format!("{}s", decision.as_secs()),
+        (None, Some(bank)) => format!("Bank {}s", bank.as_secs()),
+        (Some(decision), Some(bank)) => format!("{}s + Bank {}s", decision.as_secs(), bank.as_secs()),
+    }
+}
@@ -49,6 +49,8 @@ pub enum CardsRelativePosition {
     Enhancements(PlayerRelativePos),
     Played(PlayerRelativePos),
     Discards(PlayerRelativePos),
+    Specials(PlayerRelativePos),
+    UsedSpecials(PlayerRelativePos),
 }
 
 impl CardsRelativePosition {
@@ -62,6 +64,10 @@ impl CardsRelativePosition {
             }
             CardsRelativePosition::Played(p) => CardsPosition::Played(p.into_absolute(me)),
             CardsRelativePosition::Discards(p) => CardsPosition::Discards(p.into_absolute(me)),
+            CardsRelativePosition::Specials(p) => CardsPosition::Specials(p.into_absolute(me)),
+            CardsRelativePosition::UsedSpecials(p) => {
+                CardsPosition::UsedSpecials(p.into_absolute(me))
+            }
         }
     }
 }
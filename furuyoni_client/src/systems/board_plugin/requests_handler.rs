@@ -11,16 +11,18 @@ use bevy_tweening::Tween;
 use bevy_tweening::Tweenable;
 use furuyoni_lib::rules::cards::Card;
 use furuyoni_lib::rules::cards::CardSelector;
+use furuyoni_lib::rules::cards::CardSelectorCase;
 use furuyoni_lib::rules::events::GameEvent;
+use furuyoni_lib::rules::states::CardsViewRef;
 use furuyoni_lib::rules::states::StateView;
 use super::spread_plugin;
 use super::spread_plugin::Spread;
 use super::BoardError;
 use super::CardInspectPosition;
 use super::CardObject;
+use super::CardsObject;
+use super::LeavingCardObject;
 use super::OpenCardObject;
-use super::DeckObject;
-use super::HandObject;
 use furuyoni_lib::rules::cards::CardsPosition;
 use furuyoni_lib::rules::events::UpdateGameState;
 use bevy_tokio_tasks::TaskContext;
@@ -29,10 +31,67 @@ use super::BoardState;
 use furuyoni_lib::rules::PlayerPos;
 
 pub(crate) fn initialize_board(world: &mut World, state: StateView, me: PlayerPos) {
+    spawn_cards_of_state(world, &state, me);
+
     world.insert_resource(BoardState { 0: state });
     world.insert_resource(SelfPlayerPos { 0: me });
 }
 
+/// Lay out the cards of the state on the board, except for the deck.
+fn spawn_cards_of_state(world: &mut World, state: &StateView, me: PlayerPos) {
+    let cards_objects: Vec<_> = world
+        .query::<(Entity, &CardsObject)>()
+        .iter(world)
+        .map(|(entity, cards_object)| (entity, cards_object.position().into_absolute(me)))
+        .collect();
+
+    for (cards_object, position) in cards_objects {
+        if let CardsPosition::Deck(_) = position {
+            continue;
+        }
+
+        let cards: Vec<Option<Card>> = match state.cards_view(position) {
+            CardsViewRef::Open { cards } => cards.iter().copied().map(Some).collect(),
+            CardsViewRef::Hidden { length } => vec![None; *length],
+        };
+
+        for (index, card) in cards.into_iter().enumerate() {
+            let slot = world.run_system_once(
+                move |commands: Commands, spreads: Query<(&Spread, Option<&Children>)>| {
+                    let (spread, children) = spreads
+                        .get(cards_object)
+                        .expect("The cards should be laid out with a Spread.");
+
+                    spread_plugin::add_spread_child(
+                        commands,
+                        cards_object,
+                        spread,
+                        children,
+                        index,
+                        Duration::ZERO
+                    )
+                }
+            );
+            spawn_card(world, slot, position.player_pos(), card);
+        }
+    }
+}
+
+/// Remove the cards from the board, then initialize it with the state. Used to jump to another
+/// point of a game, and to catch up with the game after reconnecting.
+pub(crate) fn reset_board(world: &mut World, state: StateView, me: PlayerPos) {
+    world.run_system_once(
+        |mut commands: Commands, cards_objects: Query<Entity, With<CardsObject>>| {
+            // The cards, and the slots they have been spread on.
+            for cards_object in cards_objects.iter() {
+                commands.entity(cards_object).despawn_descendants();
+            }
+        }
+    );
+
+    initialize_board(world, state, me);
+}
+
 /// display the event in the board and return if the game has ended.
 pub(crate) async fn apply_event(
     ctx: &TaskContext,
@@ -41,21 +100,50 @@ pub(crate) async fn apply_event(
 ) -> Result<(), BoardError> {
     match event {
         GameEvent::StateUpdated(update) => {
-            ctx.run_on_main_thread(
-                move |ctx| -> Result<(), BoardError> {
+            let transferred_card = ctx.run_on_main_thread(
+                move |ctx| -> Result<Option<Card>, BoardError> {
                     let mut state = ctx.world.get_resource_mut::<BoardState>().unwrap();
+                    let transferred_card = match update {
+                        UpdateGameState::TransferCard { from, .. } => open_card(&state.0, from),
+                        _ => None,
+                    };
                     state.0.apply_update(update)?;
-                    Ok(())
+
+                    // Only show the card if it can still be seen at the destination.
+                    Ok(match update {
+                        UpdateGameState::TransferCard { to, .. } =>
+                            transferred_card.filter(|_| {
+                                matches!(
+                                    state.0.cards_view(to.position),
+                                    CardsViewRef::Open { .. }
+                                )
+                            }),
+                        _ => None,
+                    })
                 }
             ).await?;
 
             match update {
+                UpdateGameState::TransferCard { from, to } => {
+                    let wait_animation = ctx.run_on_main_thread(move |ctx| {
+                        let world = ctx.world;
+                        let card_id = card_entity(
+                            from.position,
+                            Some(from.case),
+                            world,
+                            me,
+                            transferred_card
+                        );
+                        move_card(world, card_id, from.position, to, me)
+                    }).await;
+
+                    tokio::time::sleep(wait_animation).await;
+                }
                 UpdateGameState::TransferCardFromHidden { from, to, card } => {
                     let wait_animation = ctx.run_on_main_thread(move |ctx| {
                         let world = ctx.world;
-                        let card_id = card_entity(from, world, me, card);
-                        let slot_id = slot_entity(from, to, world, me);
-                        animate_card(world, card_id, slot_id, me, from, to.position)
+                        let card_id = card_entity(from, None, world, me, card);
+                        move_card(world, card_id, from, to, me)
                     }).await;
 
                     tokio::time::sleep(wait_animation).await;
@@ -64,13 +152,47 @@ pub(crate) async fn apply_event(
             }
         }
         GameEvent::PerformBasicAction { .. } => {/* Todo */}
-        GameEvent::GameEnd { result: _ } => {
+        GameEvent::PlayCard { .. } => {/* Todo */}
+        GameEvent::Attack(_) => {/* Todo */}
+        GameEvent::TakeDamage { .. } => {/* Todo */}
+        GameEvent::Disenhance { .. } => {/* Todo */}
+        GameEvent::ReshuffleDeck { .. } => {/* Todo */}
+        GameEvent::GameEnd(_) => {
             // TODO:
         }
     }
     Ok(())
 }
 
+/// Move the card to its place at the destination, and return how long to wait for the animation.
+/// A card moved into the deck is removed once it has reached the deck.
+fn move_card(
+    world: &mut World,
+    card_id: Entity,
+    from: CardsPosition,
+    to: CardSelector,
+    me: PlayerPos
+) -> Duration {
+    let dest_id = slot_entity(from, to, world, me);
+    let wait_animation = animate_card(world, card_id, dest_id, me, from, to.position);
+
+    if let CardsPosition::Deck(_) = to.position {
+        world.entity_mut(card_id).insert(LeavingCardObject::new(CARD_MOVE_DURATION));
+    }
+
+    wait_animation
+}
+
+/// The card which the selector points to, if it can be seen.
+fn open_card(state: &StateView, selector: CardSelector) -> Option<Card> {
+    match state.cards_view(selector.position) {
+        CardsViewRef::Open { cards } => cards.get(selector.case.index(cards.len())).copied(),
+        CardsViewRef::Hidden { .. } => None,
+    }
+}
+
+const CARD_MOVE_DURATION: Duration = Duration::from_secs(1);
+
 fn animate_card(
     world: &mut World,
     card_id: Entity,
@@ -142,7 +264,7 @@ fn animate_card(
                             transform_tween(
                                 &card_local,
                                 &Transform::IDENTITY,
-                                Duration::from_secs(1)
+                                CARD_MOVE_DURATION
                             )
                         ),
                     ),
@@ -175,89 +297,135 @@ fn transform_tween(
     bevy_tweening::Tracks::new([tween_translation, tween_scale])
 }
 
+/// Add a slot for the card at the destination, and return it. The cards in the deck aren't laid
+/// out, so the deck itself is returned for it.
 pub(crate) fn slot_entity(
     from: CardsPosition,
     to: CardSelector,
     world: &mut World,
     me: PlayerPos
 ) -> Entity {
-    match to.position {
-        CardsPosition::Hand(p) => {
-            world.run_system_once(
-                move |
-                    commands: Commands,
-                    hand_objects: Query<(Entity, &HandObject, &Spread, Option<&Children>)>
-                | {
-                    let (hand_id, _, hand_animation, children) = hand_objects
-                        .iter()
-                        .find(|&(_, h, _, _)| { h.relative_pos.into_absolute(me) == p })
-                        .unwrap();
-                    let new = spread_plugin::add_spread_child(
-                        commands,
-                        hand_id,
-                        hand_animation,
-                        children,
-                        // FIXME: Refactor - group delay calculations together.
-                        Duration::from_secs_f32(match from {
-                            CardsPosition::Deck(p) if p == me => 0.8,
-                            _ => 0.25,
-                        })
-                    );
-                    new
-                }
+    let cards_object = cards_object_entity(world, to.position, me);
+    if let CardsPosition::Deck(_) = to.position {
+        return cards_object;
+    }
+
+    world.run_system_once(
+        move |commands: Commands, spreads: Query<(&Spread, Option<&Children>)>| {
+            let (spread, children) = spreads
+                .get(cards_object)
+                .expect("The cards should be laid out with a Spread.");
+            let index = to.case.index(children.map_or(0, |children| children.len()));
+
+            spread_plugin::add_spread_child(
+                commands,
+                cards_object,
+                spread,
+                children,
+                index,
+                // FIXME: Refactor - group delay calculations together.
+                Duration::from_secs_f32(match from {
+                    CardsPosition::Deck(p) if p == me => 0.8,
+                    _ => 0.25,
+                })
             )
         }
-        CardsPosition::Deck(_) => todo!(),
-        CardsPosition::Playing(_) => todo!(),
-        CardsPosition::Enhancements(_) => todo!(),
-        CardsPosition::Played(_) => todo!(),
-        CardsPosition::Discards(_) => todo!(),
-    }
+    )
 }
 
+/// Return the card to move out of the position, taken out of its slot while staying where it is
+/// on the screen, and showing the given face. The cards of a hidden position can't be told apart,
+/// so any of them is taken if `case` is `None`.
 pub(crate) fn card_entity(
     from: CardsPosition,
+    case: Option<CardSelectorCase>,
     world: &mut World,
     me: PlayerPos,
     card: Option<Card>
 ) -> Entity {
     let owner = from.player_pos();
-    let spawn_from = match from {
-        CardsPosition::Deck(p) =>
-            world.run_system_once(
-                move |mut commands: Commands, deck_objects: Query<(Entity, &DeckObject)>| {
-                    let (deck_id, _) = deck_objects
-                        .iter()
-                        .find(|&(_, d)| { d.relative_pos.into_absolute(me) == p })
-                        .unwrap();
-
-                    deck_id
-                }
-            ),
-        CardsPosition::Hand(_) => todo!(),
-        CardsPosition::Discards(_) => todo!(),
-        CardsPosition::Playing(_) | CardsPosition::Enhancements(_) | CardsPosition::Played(_) =>
-            panic!("Impossible event."),
+    let cards_object = cards_object_entity(world, from, me);
+
+    if let CardsPosition::Deck(_) = from {
+        // The cards in the deck aren't on the board.
+        return spawn_card(world, cards_object, owner, card);
+    }
+
+    let slots = world
+        .get::<Children>(cards_object)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    let slot = match case {
+        Some(case) => slots.get(case.index(slots.len())),
+        None => slots.last(),
     };
+    let Some(&slot) = slot else {
+        warn!("There is no card to take from {from:?} on the board.");
+        return spawn_card(world, cards_object, owner, card);
+    };
+    let card_id = world
+        .get::<Children>(slot)
+        .and_then(|children| children.first().copied())
+        .expect("A slot should hold a card.");
 
-    world.run_system_once(move |mut commands: Commands, asset_server: Res<AssetServer>| {
-        let texture = match card {
-            Some(_) => asset_server.load("sprites/cardfront_empty.png"),
-            None => asset_server.load("sprites/cardback_normal.png"),
-        };
+    // Put the card directly under the cards object, so that the slot can be removed.
+    let (card_global, parent_global) = world.run_system_once(
+        move |transform_helper: TransformHelper| {
+            let card_global = transform_helper.compute_global_transform(card_id).unwrap();
+            let parent_global = transform_helper.compute_global_transform(cards_object).unwrap();
+
+            (card_global, parent_global)
+        }
+    );
+    world.run_system_once(
+        move |mut commands: Commands, mut transforms: Query<&mut Transform>| {
+            *transforms.get_mut(card_id).unwrap() = card_global.reparented_to(&parent_global);
+            commands.entity(card_id).set_parent(cards_object);
+        }
+    );
+    spread_plugin::remove_spread_child(world, slot);
+
+    show_card_face(world, card_id, card);
+    card_id
+}
+
+fn cards_object_entity(world: &mut World, position: CardsPosition, me: PlayerPos) -> Entity {
+    world
+        .query::<(Entity, &CardsObject)>()
+        .iter(world)
+        .find(|(_, cards_object)| cards_object.position().into_absolute(me) == position)
+        .map(|(entity, _)| entity)
+        .unwrap_or_else(|| panic!("There is no place for {position:?} on the board."))
+}
+
+fn spawn_card(world: &mut World, parent: Entity, owner: PlayerPos, card: Option<Card>) -> Entity {
+    let card_id = world
+        .spawn((SpriteBundle::default(), CardObject::new(owner)))
+        .set_parent(parent)
+        .id();
+
+    show_card_face(world, card_id, card);
+    card_id
+}
+
+/// Show the front of the card if it's known, or its back otherwise.
+fn show_card_face(world: &mut World, card_id: Entity, card: Option<Card>) {
+    let asset_server = world.resource::<AssetServer>();
+    let texture: Handle<Image> = match card {
+        Some(_) => asset_server.load("sprites/cardfront_empty.png"),
+        None => asset_server.load("sprites/cardback_normal.png"),
+    };
 
-        let mut new = commands.spawn((
-            SpriteBundle {
-                texture,
-                ..default()
-            },
-            CardObject::new(owner),
-        ));
-        if let Some(card) = card {
-            new.insert(OpenCardObject::new(card));
+    let mut card_object = world.entity_mut(card_id);
+    card_object.insert(texture);
+    match card {
+        Some(card) => {
+            card_object.insert(OpenCardObject::new(card));
         }
-        new.set_parent(spawn_from).id()
-    })
+        None => {
+            card_object.remove::<OpenCardObject>();
+        }
+    }
 }
 
 pub(crate) async fn check_game_state(ctx: &TaskContext, state: StateView) {
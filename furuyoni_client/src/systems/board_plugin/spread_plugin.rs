@@ -49,33 +49,46 @@ fn tick_spread(
     }
 }
 
+/// Add an element at the index of the spread. The elements are moved to make room for it once
+/// the delay has passed.
 pub(crate) fn add_spread_child(
     mut commands: Commands,
     entity: Entity,
     spread: &Spread,
     op_children: Option<&Children>,
+    index: usize,
     delay: Duration
 ) -> Entity {
     let children_count = match op_children {
         Some(children) => children.len(),
         None => 0,
     };
+    let index = index.min(children_count);
 
     let new = commands
         .spawn((
             SpatialBundle::from_transform(
-                Transform::from_translation(
-                    element_position(spread, children_count, children_count + 1)
-                )
+                Transform::from_translation(element_position(spread, index, children_count + 1))
             ),
             SpreadElement { activate_timer: Timer::new(delay, TimerMode::Once) },
         ))
         .id();
-    commands.entity(entity).add_child(new);
+    commands.entity(entity).insert_children(index, &[new]);
 
     new
 }
 
+/// Remove the element from its spread, and move the other elements to fill its place.
+pub(crate) fn remove_spread_child(world: &mut World, element: Entity) {
+    let parent = world.get::<Parent>(element).map(|parent| parent.get());
+
+    world.entity_mut(element).despawn_recursive();
+
+    if let Some(parent) = parent {
+        world.send_event(SpreadElementActivatedEvent(parent));
+    }
+}
+
 fn animate_spread(
     mut commands: Commands,
     spread_query: Query<(&Spread, &Children)>,
@@ -83,9 +96,10 @@ fn animate_spread(
     mut ev_changed: EventReader<SpreadElementActivatedEvent>
 ) {
     for ev in ev_changed.read() {
-        let (spread, children) = spread_query
-            .get(ev.0)
-            .expect("A parent of a SpreadElement should have Spread as its component.");
+        let Ok((spread, children)) = spread_query.get(ev.0) else {
+            // The spread has no elements left, or has been removed from the board.
+            continue;
+        };
         let elements: Vec<_> = children
             .iter()
             .map(|&child|
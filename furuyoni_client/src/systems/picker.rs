@@ -1,7 +1,22 @@
+use crate::systems::board_plugin::{
+    CardObject,
+    CardsObject,
+    CardsRelativePosition,
+    PlayerRelativePos,
+};
 use bevy::ecs::system::RunSystemOnce;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use bevy_tokio_tasks::TaskContext;
-use furuyoni_lib::rules::player_actions::{ BasicAction, BasicActionCost };
+use furuyoni_lib::rules::attack::DamageTarget;
+use furuyoni_lib::rules::player_actions::{
+    BasicAction,
+    BasicActionCost,
+    HandSelector,
+    PlayableCardSelector,
+    ReactionAction,
+    SpecialSelector,
+};
 use serde::{ Deserialize, Serialize };
 use std::sync::Arc;
 use tokio::sync::oneshot;
@@ -9,20 +24,28 @@ use tokio::sync::oneshot;
 pub struct PickerPlugin;
 
 pub enum PickMainPhaseActionResult {
+    PlayCard(PlayableCardSelector),
     PayBasicActionCost(BasicActionCost),
     EndMainPhase,
 }
 
 pub async fn pick_main_phase_action(
     mut ctx: TaskContext,
+    playable_cards: Arc<Vec<PlayableCardSelector>>,
     allowed_costs: Arc<Vec<BasicActionCost>>
 ) -> PickMainPhaseActionResult {
     loop {
-        let allowed_costs = allowed_costs.clone();
+        let predicate_playable_cards = playable_cards.clone();
+        let predicate_allowed_costs = allowed_costs.clone();
         let picked = pick_anything(&mut ctx, move |p| {
             match p {
                 Pickable::EndMainPhase => true,
-                Pickable::Vigor => allowed_costs.contains(&BasicActionCost::Vigor),
+                Pickable::Vigor => predicate_allowed_costs.contains(&BasicActionCost::Vigor),
+                Pickable::HandCard(hand) =>
+                    predicate_playable_cards.contains(&PlayableCardSelector::Hand(hand)) ||
+                    predicate_allowed_costs.contains(&BasicActionCost::Hand(hand)),
+                Pickable::Special(special) =>
+                    predicate_playable_cards.contains(&PlayableCardSelector::Special(special)),
                 _ => false,
             }
         }).await;
@@ -34,11 +57,120 @@ pub async fn pick_main_phase_action(
             Pickable::Vigor => {
                 return PickMainPhaseActionResult::PayBasicActionCost(BasicActionCost::Vigor);
             }
+            Pickable::HandCard(hand) => {
+                let card = PlayableCardSelector::Hand(hand);
+                let cost = BasicActionCost::Hand(hand);
+
+                let play = match (playable_cards.contains(&card), allowed_costs.contains(&cost)) {
+                    // Any card in hand can be used for a basic action instead of being played.
+                    (true, true) =>
+                        pick_option(&ctx, vec!["Play".to_string(), "Basic action".to_string()])
+                            .await == 0,
+                    (true, false) => true,
+                    (false, true) => false,
+                    (false, false) => {
+                        continue;
+                    }
+                };
+
+                return if play {
+                    PickMainPhaseActionResult::PlayCard(card)
+                } else {
+                    PickMainPhaseActionResult::PayBasicActionCost(cost)
+                };
+            }
+            Pickable::Special(special) => {
+                return PickMainPhaseActionResult::PlayCard(PlayableCardSelector::Special(special));
+            }
             _ => {/*retry */}
         }
     }
 }
 
+/// Show the options as buttons, and return the index of the picked one.
+pub async fn pick_option(ctx: &TaskContext, options: Vec<String>) -> usize {
+    let options_count = options.len();
+
+    let buttons = ctx.run_on_main_thread(move |ctx| {
+        ctx.world.run_system_once_with(options, spawn_option_buttons)
+    }).await;
+
+    let picked = loop {
+        let picked = pick_anything(ctx, move |p| {
+            match p {
+                Pickable::Option(i) => i < options_count,
+                _ => false,
+            }
+        }).await;
+
+        if let Pickable::Option(i) = picked {
+            break i;
+        }
+    };
+
+    ctx.run_on_main_thread(move |ctx| {
+        ctx.world.entity_mut(buttons).despawn_recursive();
+    }).await;
+
+    picked
+}
+
+fn spawn_option_buttons(
+    In(options): In<Vec<String>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>
+) -> Entity {
+    let font = asset_server.load("fonts/Fira_Sans/FiraSans-Regular.ttf");
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (i, option) in options.into_iter().enumerate() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                min_width: Val::Px(250.0),
+                                height: Val::Px(65.0),
+                                padding: UiRect::horizontal(Val::Px(20.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.3, 0.5).into(),
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        PickerButton {
+                            pickable: Pickable::Option(i),
+                        },
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(
+                            TextBundle::from_section(option, TextStyle {
+                                font: font.clone(),
+                                font_size: 40.0,
+                                color: Color::srgb(0.9, 0.9, 0.9),
+                            })
+                        );
+                    });
+            }
+        })
+        .id()
+}
+
 pub enum PickBasicActionResult {
     BasicAction(BasicAction),
     Cancel,
@@ -70,18 +202,128 @@ pub async fn pick_basic_action(
     }
 }
 
+pub async fn pick_damage_target(
+    ctx: &TaskContext,
+    available_targets: Arc<Vec<DamageTarget>>
+) -> DamageTarget {
+    loop {
+        let available_targets = available_targets.clone();
+        let picked = pick_anything(&ctx, move |p| {
+            match p {
+                Pickable::DamageTarget(t) => available_targets.contains(&t),
+                _ => false,
+            }
+        }).await;
+
+        match picked {
+            Pickable::DamageTarget(target) => {
+                return target;
+            }
+            _ => {/*retry */}
+        }
+    }
+}
+
+pub async fn pick_reaction(
+    ctx: &TaskContext,
+    playable_reactions: Arc<Vec<PlayableCardSelector>>
+) -> ReactionAction {
+    loop {
+        let predicate_playable_reactions = playable_reactions.clone();
+        let picked = pick_anything(&ctx, move |p| {
+            match p {
+                Pickable::Pass => true,
+                Pickable::HandCard(hand) =>
+                    predicate_playable_reactions.contains(&PlayableCardSelector::Hand(hand)),
+                Pickable::Special(special) =>
+                    predicate_playable_reactions.contains(&PlayableCardSelector::Special(special)),
+                _ => false,
+            }
+        }).await;
+
+        match picked {
+            Pickable::Pass => {
+                return ReactionAction::Pass;
+            }
+            Pickable::HandCard(hand) => {
+                return ReactionAction::PlayReaction(PlayableCardSelector::Hand(hand));
+            }
+            Pickable::Special(special) => {
+                return ReactionAction::PlayReaction(PlayableCardSelector::Special(special));
+            }
+            _ => {/*retry */}
+        }
+    }
+}
+
+/// Let the player pick cards in the hand, one by one, and confirm them. Picking a picked card
+/// again unpicks it. Any number of cards can be picked if the amount is `None`.
+pub async fn pick_hand_cards(ctx: &TaskContext, amount: Option<usize>) -> Vec<HandSelector> {
+    let mut picked_cards: Vec<HandSelector> = vec![];
+
+    loop {
+        let selected = picked_cards.clone();
+        ctx.run_on_main_thread(move |ctx| {
+            ctx.world.insert_resource(SelectedHandCards(selected));
+        }).await;
+
+        let can_confirm = amount.map_or(true, |amount| picked_cards.len() == amount);
+        let picked = pick_anything(&ctx, move |p| {
+            match p {
+                Pickable::Confirm => can_confirm,
+                Pickable::HandCard(_) => true,
+                _ => false,
+            }
+        }).await;
+
+        match picked {
+            Pickable::Confirm => {
+                break;
+            }
+            Pickable::HandCard(hand) => {
+                if let Some(i) = picked_cards.iter().position(|&h| h == hand) {
+                    picked_cards.remove(i);
+                } else {
+                    picked_cards.push(hand);
+                }
+            }
+            _ => {/*retry */}
+        }
+    }
+
+    ctx.run_on_main_thread(move |ctx| {
+        ctx.world.remove_resource::<SelectedHandCards>();
+    }).await;
+
+    picked_cards
+}
+
+type PickablePredicate = Arc<dyn (Fn(Pickable) -> bool) + Send + Sync>;
+
 #[derive(Resource)]
 struct PickerCallBack {
     sender: Option<oneshot::Sender<Pickable>>,
+    /// Whether a card can be picked. Buttons that can't be picked are hidden instead.
+    predicate: PickablePredicate,
 }
 
+/// The cards in the hand picked so far by `pick_hand_cards`.
+#[derive(Resource)]
+struct SelectedHandCards(Vec<HandSelector>);
+
 impl Plugin for PickerPlugin {
     fn build(&self, app: &mut App) {
         app
             // .add_state::<PickingState>()
             .register_type::<Pickable>()
             .register_type::<PickerButton>()
-            .add_systems(Update, (poll_pickers.run_if(resource_exists::<PickerCallBack>),));
+            .add_systems(
+                Update,
+                (
+                    (poll_pickers, poll_card_pickers).run_if(resource_exists::<PickerCallBack>),
+                    tint_selected_hand_cards,
+                )
+            );
     }
 }
 
@@ -97,8 +339,16 @@ pub enum Pickable {
     #[default]
     Cancel,
     EndMainPhase,
+    Pass,
+    Confirm,
     BasicAction(BasicAction),
     Vigor,
+    DamageTarget(DamageTarget),
+    HandCard(HandSelector),
+    /// An unused special card of mine.
+    Special(SpecialSelector),
+    /// One of the options shown by `pick_option`.
+    Option(usize),
 }
 
 async fn pick_anything(
@@ -106,9 +356,13 @@ async fn pick_anything(
     predicate: impl (Fn(Pickable) -> bool) + Send + Sync + 'static
 ) -> Pickable {
     let (tx, rx) = oneshot::channel();
+    let predicate: PickablePredicate = Arc::new(predicate);
 
     ctx.dispatch_to_main_thread(move |ctx| {
-        ctx.world.insert_resource(PickerCallBack { sender: Some(tx) });
+        ctx.world.insert_resource(PickerCallBack {
+            sender: Some(tx),
+            predicate: predicate.clone(),
+        });
         ctx.world.run_system_once(enable_pickers_with(predicate));
     });
 
@@ -124,7 +378,7 @@ async fn pick_anything(
 }
 
 fn enable_pickers_with(
-    predicate: impl (Fn(Pickable) -> bool) + Send + Sync
+    predicate: PickablePredicate
 ) -> impl Fn(Query<(&PickerButton, &mut Visibility)>) + Send + Sync {
     move |mut query: Query<(&PickerButton, &mut Visibility)>| {
         for (picker_button, mut visibility) in query.iter_mut() {
@@ -165,6 +419,114 @@ fn poll_pickers(
     }
 }
 
+/// Pick the card under the cursor when the mouse is clicked.
+fn poll_card_pickers(
+    mut callback: ResMut<PickerCallBack>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    cards: Query<(Entity, &GlobalTransform, &Handle<Image>), With<CardObject>>,
+    parents: Query<&Parent>,
+    cards_objects: Query<(&CardsObject, &Children)>,
+    images: Res<Assets<Image>>
+) {
+    if callback.sender.is_none() || !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let Some(cursor) = windows.get_single().ok().and_then(|window| window.cursor_position()) else {
+        return;
+    };
+    let Some(cursor) = cameras
+        .iter()
+        .find_map(|(camera, camera_transform)| {
+            camera.viewport_to_world_2d(camera_transform, cursor)
+        }) else {
+        return;
+    };
+
+    // The card on the top among the cards under the cursor.
+    let clicked = cards
+        .iter()
+        .filter(|(_, global, texture)| {
+            let Some(image) = images.get(*texture) else {
+                return false;
+            };
+            let half_size = image.size_f32() / 2.0;
+            let local = global.affine().inverse().transform_point3(cursor.extend(0.0));
+
+            local.x.abs() <= half_size.x && local.y.abs() <= half_size.y
+        })
+        .max_by(|(_, a, _), (_, b, _)| a.translation().z.total_cmp(&b.translation().z));
+    let Some((card, _, _)) = clicked else {
+        return;
+    };
+
+    // A card is the child of a slot of the cards object.
+    let Ok(slot) = parents.get(card).map(Parent::get) else {
+        return;
+    };
+    let Ok((cards_object, slots)) = parents
+        .get(slot)
+        .and_then(|cards_object| cards_objects.get(cards_object.get())) else {
+        return;
+    };
+    let Some(index) = slots.iter().position(|&s| s == slot) else {
+        return;
+    };
+
+    let pickable = match cards_object.position() {
+        CardsRelativePosition::Hand(PlayerRelativePos::Me) => {
+            Pickable::HandCard(HandSelector(index))
+        }
+        CardsRelativePosition::Specials(PlayerRelativePos::Me) => {
+            Pickable::Special(SpecialSelector(index))
+        }
+        _ => {
+            return;
+        }
+    };
+
+    if (callback.predicate)(pickable) {
+        let sender = callback.sender.take().unwrap();
+        // The pick may have been cancelled.
+        let _ = sender.send(pickable);
+    }
+}
+
+const SELECTED_CARD_COLOR: Color = Color::srgb(0.6, 0.8, 1.0);
+
+/// Tint the cards in my hand picked by `pick_hand_cards`.
+fn tint_selected_hand_cards(
+    selected: Option<Res<SelectedHandCards>>,
+    cards_objects: Query<(&CardsObject, &Children)>,
+    slots: Query<&Children>,
+    mut sprites: Query<&mut Sprite, With<CardObject>>
+) {
+    let Some((_, hand_slots)) = cards_objects
+        .iter()
+        .find(|(cards_object, _)| {
+            matches!(cards_object.position(), CardsRelativePosition::Hand(PlayerRelativePos::Me))
+        }) else {
+        return;
+    };
+
+    for (i, &slot) in hand_slots.iter().enumerate() {
+        let is_selected = selected
+            .as_ref()
+            .is_some_and(|selected| selected.0.contains(&HandSelector(i)));
+        let color = if is_selected { SELECTED_CARD_COLOR } else { Color::WHITE };
+
+        for &card in slots.get(slot).into_iter().flatten() {
+            if let Ok(mut sprite) = sprites.get_mut(card) {
+                if sprite.color != color {
+                    sprite.color = color;
+                }
+            }
+        }
+    }
+}
+
 fn disable_picker_buttons(mut buttons: Query<&mut Visibility, With<PickerButton>>) {
     for mut v in buttons.iter_mut() {
         *v = Visibility::Hidden;
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use bevy_tokio_tasks::TaskContext;
+use furuyoni_lib::rules::{ ObservePosition, PlayerPos };
+use tokio::sync::mpsc;
+
+pub struct ReplayControlsPlugin;
+
+#[derive(Debug, Copy, Clone)]
+pub enum ReplayCommand {
+    TogglePause,
+    StepForward,
+    StepBack,
+    SpeedUp,
+    SlowDown,
+    SetPerspective(ObservePosition),
+}
+
+#[derive(Resource)]
+struct ReplayControls {
+    sender: mpsc::UnboundedSender<ReplayCommand>,
+}
+
+impl Plugin for ReplayControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (send_replay_commands.run_if(resource_exists::<ReplayControls>),));
+    }
+}
+
+/// Start listening to the replay controls of the keyboard.
+pub async fn listen_replay_commands(ctx: &TaskContext) -> mpsc::UnboundedReceiver<ReplayCommand> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    ctx.run_on_main_thread(move |ctx| {
+        ctx.world.insert_resource(ReplayControls { sender });
+    }).await;
+
+    receiver
+}
+
+/// Space: play or pause, Right/Left: step forward or back, Up/Down: speed up or slow down,
+/// 1/2/3: view from P1, P2 or the master view.
+fn send_replay_commands(keys: Res<ButtonInput<KeyCode>>, controls: Res<ReplayControls>) {
+    let perspective = ReplayCommand::SetPerspective;
+    let bindings = [
+        (KeyCode::Space, ReplayCommand::TogglePause),
+        (KeyCode::ArrowRight, ReplayCommand::StepForward),
+        (KeyCode::ArrowLeft, ReplayCommand::StepBack),
+        (KeyCode::ArrowUp, ReplayCommand::SpeedUp),
+        (KeyCode::ArrowDown, ReplayCommand::SlowDown),
+        (KeyCode::Digit1, perspective(ObservePosition::RelativeTo(PlayerPos::P1))),
+        (KeyCode::Digit2, perspective(ObservePosition::RelativeTo(PlayerPos::P2))),
+        (KeyCode::Digit3, perspective(ObservePosition::MasterView)),
+    ];
+
+    for (key, command) in bindings {
+        if keys.just_pressed(key) {
+            // The replay may have been closed.
+            let _ = controls.sender.send(command);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 #![feature(let_chains)]
 
 pub mod net;
+pub mod replays;
 pub mod rules;
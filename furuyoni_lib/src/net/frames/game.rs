@@ -1,11 +1,14 @@
+use crate::rules::attack::{AttackDamage, DamageTarget};
 use crate::rules::player_actions::{
-    BasicAction, BasicActionCost, MainPhaseAction, PlayableCardSelector,
+    BasicAction, BasicActionCost, HandSelector, MainPhaseAction, PlayableCardSelector,
+    ReactionAction,
 };
 use crate::rules::PlayerPos;
 
 use crate::rules::events::GameEvent;
 use crate::rules::states::StateView;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum GameToPlayerMessage {
@@ -13,22 +16,62 @@ pub enum GameToPlayerMessage {
     Response(GameToPlayerResponse),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum GameToPlayerRequest {
     NotifyEvent(GameEvent),
+    /// The player's last response has been refused for the given reason.
+    NotifyRejection(String),
+    /// The time the player has for the upcoming decision.
+    NotifyTimeLeft(TimeLeft),
     InitializeGameState(StateView),
     CheckGameState(StateView),
     RequestMainPhaseAction(RequestMainPhaseAction),
+    RequestDamageTarget(RequestDamageTarget),
+    RequestReaction(RequestReaction),
+    RequestEffectChoice(RequestEffectChoice),
+    RequestDiscard(RequestDiscard),
+    RequestMulligan,
+    RequestReshuffle,
     RequestGameStart { pos: PlayerPos },
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct TimeLeft {
+    /// The time given to every decision, spent before the time bank.
+    pub decision: Option<Duration>,
+    /// The time left in the player's bank for the rest of the game.
+    pub bank: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct RequestMainPhaseAction {
     pub playable_cards: Vec<PlayableCardSelector>,
     pub performable_basic_actions: Vec<BasicAction>,
     pub available_basic_action_costs: Vec<BasicActionCost>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestDamageTarget {
+    pub damage: AttackDamage,
+    pub available_targets: Vec<DamageTarget>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestReaction {
+    pub attack_damage: AttackDamage,
+    pub playable_reactions: Vec<PlayableCardSelector>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestEffectChoice {
+    pub options: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestDiscard {
+    pub amount: usize,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum GameToPlayerResponse {
     State(StateView),
@@ -41,10 +84,16 @@ pub enum PlayerToGameMessage {
     Request(PlayerToGameRequest),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PlayerToGameResponse {
     AcknowledgeGameStart,
     MainPhaseAction(MainPhaseAction),
+    DamageTarget(DamageTarget),
+    Reaction(ReactionAction),
+    EffectChoice(usize),
+    Discard(Vec<HandSelector>),
+    Mulligan(Vec<HandSelector>),
+    Reshuffle(bool),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,4 +1,21 @@
+use crate::rules::states::PlayersData;
 use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+/// The version of the protocol. A client of a different version is refused on the handshake.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SessionId(pub u64);
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerId(pub u64);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlayerIdentity {
+    pub id: PlayerId,
+    pub display_name: String,
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum LobbyToPlayerMessage {
@@ -13,7 +30,14 @@ pub enum LobbyToPlayerRequest {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub enum LobbyToPlayerNotification {}
+pub enum LobbyToPlayerNotification {
+    /// The player is waiting for a match. `position` is 0 for the player who is matched next.
+    QueueStatus { position: u32, queue_len: u32 },
+    /// The player has been matched and the game is starting.
+    MatchFound,
+    /// The player's game couldn't start for the given reason. The player is back in the lobby.
+    GameCancelled(String),
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum LobbyToPlayerRequestData {
@@ -22,17 +46,34 @@ pub enum LobbyToPlayerRequestData {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum LobbyToPlayerResponse {
+    /// The handshake has been accepted.
+    SessionStarted {
+        session_id: SessionId,
+        identity: PlayerIdentity,
+    },
     RoomsList(Vec<LobbyRoomInfo>),
-    RoonEnterSuccess(bool)
+    RoonEnterSuccess(bool),
+    /// The room has been created and the player has entered it.
+    RoomCreated(u32),
+    GamesList(Vec<LobbyGameInfo>),
+    Ack,
+    /// The request has been refused for the given reason.
+    Refused(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LobbyRoomInfo{
-    roon_id: u32,
-    room_name: String,
-    room_description: String,
-    current_player_num: u32,
-    max_player_num: u32,
+    pub room_id: u32,
+    pub room_name: String,
+    pub room_description: String,
+    pub current_player_num: u32,
+    pub max_player_num: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LobbyGameInfo {
+    pub game_id: u32,
+    pub players: PlayersData<PlayerIdentity>,
 }
 
 
@@ -50,12 +91,56 @@ pub enum PlayerToLobbyResponse {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum PlayerToLobbyRequest {
+    /// Should be the first message of a connection.
+    Handshake(PlayerToLobbyHandshake),
     GetRoomsList,
+    CreateRoom(PlayerToLobbyCreateRoom),
     TryEnterRoom(PlayerToLobbyTryEnterRoom),
+    LeaveRoom,
+    /// The game starts when every player in a full room is ready.
+    Ready(bool),
+    JoinMatchmaking,
+    LeaveMatchmaking,
+    /// The games being played that can be watched.
+    GetGamesList,
+    /// Watch a game as a bystander. The game is streamed through the game channels.
+    Spectate(PlayerToLobbySpectate),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PlayerToLobbyCreateRoom{
+    pub room_name: String,
+    pub room_description: String,
+    /// Whether a player who runs out of time loses the game of the room, instead of having the
+    /// default decision made for them.
+    #[serde(default)]
+    pub forfeit_on_timeout: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PlayerToLobbyTryEnterRoom{
     pub room_id: u32,
-    pub player_id: u32
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PlayerToLobbySpectate {
+    pub game_id: u32,
+    /// How far behind the game the spectator sees it.
+    pub delay: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PlayerToLobbyHandshake {
+    pub protocol_version: u32,
+    pub credentials: Credentials,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Credentials {
+    /// Play as a guest with a new identity.
+    DisplayName(String),
+    /// Play as the identity the token belongs to.
+    Token(String),
+    /// Resume the session of a dropped connection.
+    Resume(SessionId),
 }
\ No newline at end of file
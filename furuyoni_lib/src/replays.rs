@@ -0,0 +1,95 @@
+use crate::net::frames::{ GameToPlayerRequest, PlayerIdentity, PlayerToGameResponse };
+use crate::rules::deck_list::DeckList;
+use crate::rules::events::GameEvent;
+use crate::rules::states::{ EventFilterError, GameState, InvalidGameUpdateError, Phase, PlayersData };
+use crate::rules::PlayerPos;
+use serde::{ Deserialize, Serialize };
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+mod replay_player;
+
+pub use replay_player::ReplayPlayer;
+
+/// The version of the replay format. Should be bumped on every change to `Replay`, or to how a
+/// game is played from its seed.
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Failed to read the replay file: {0}")]
+    ReadFailed(#[from] std::io::Error),
+    #[error("Failed to parse the replay: {0}")]
+    ParseFailed(#[from] serde_json::Error),
+    #[error("Unsupported replay format version: {0}.")]
+    UnsupportedVersion(u32),
+    #[error("The event at {index} can't be applied to the state: {error}")]
+    InvalidUpdate {
+        index: usize,
+        error: InvalidGameUpdateError,
+    },
+    #[error("{0}")]
+    FilterFailed(#[from] EventFilterError),
+    #[error("The game never reaches the {phase:?} phase of turn {turn}.")]
+    PointNotReached {
+        turn: u32,
+        phase: Phase,
+    },
+}
+
+/// Everything needed to watch a finished game again.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Replay {
+    pub version: u32,
+    pub players: PlayersData<PlayerIdentity>,
+    pub deck_lists: PlayersData<DeckList>,
+    /// The seed of the game's random number generator.
+    pub seed: u64,
+    pub initial_game_state: GameState,
+    /// Every event of the game, as seen from the master view.
+    pub events: Vec<GameEvent>,
+    /// Every decision of the players, in the order they have been made.
+    pub decisions: Vec<DecisionRecord>,
+}
+
+/// A decision requested from a player, and how the player has responded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DecisionRecord {
+    pub player: PlayerPos,
+    /// The number of events that had happened when the decision was requested.
+    pub event_index: usize,
+    pub request: GameToPlayerRequest,
+    /// `None` if the player has failed to respond.
+    pub response: Option<PlayerToGameResponse>,
+    /// How long the player has taken to respond.
+    pub latency: Duration,
+    /// The reason the response has been rejected, if it has. A rejected decision is requested
+    /// again as a new decision.
+    pub rejection: Option<String>,
+}
+
+/// Only the version, to check it before parsing the rest which may be in another format.
+#[derive(Deserialize)]
+struct ReplayVersion {
+    version: u32,
+}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ReplayError> {
+        let ReplayVersion { version } = serde_json::from_str(json)?;
+        if version != REPLAY_FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, ReplayError> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
@@ -0,0 +1,273 @@
+use crate::replays::{ Replay, ReplayError };
+use crate::rules::events::{ GameEvent, UpdateGameState };
+use crate::rules::states::{ filter_event, filter_state, GameState, Phase, StateView };
+use crate::rules::ObservePosition;
+
+/// Steps through a replay, rebuilding the state of the game at any point of it.
+pub struct ReplayPlayer {
+    replay: Replay,
+    /// The state after applying every event before `position`.
+    state: GameState,
+    position: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let state = replay.initial_game_state.clone();
+        Self { replay, state, position: 0 }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The index of the event to be applied next. 0 is the start of the game, and `len()` is the
+    /// end of it.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The number of events in the replay.
+    pub fn len(&self) -> usize {
+        self.replay.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replay.events.is_empty()
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position >= self.len()
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Return the current state as seen from the given position.
+    pub fn state_view(&self, viewed_from: ObservePosition) -> StateView {
+        filter_state(viewed_from, &self.state)
+    }
+
+    /// Return the next event as seen from the given position without applying it, or `None` at
+    /// the end of the game.
+    pub fn next_event(&self, viewed_from: ObservePosition) -> Result<Option<GameEvent>, ReplayError> {
+        let Some(&event) = self.replay.events.get(self.position) else {
+            return Ok(None);
+        };
+
+        Ok(Some(filter_event(&self.state, viewed_from, event)?))
+    }
+
+    /// Apply the next event, and return it as seen from the given position. Returns `None` at the
+    /// end of the game.
+    pub fn step_forward(
+        &mut self,
+        viewed_from: ObservePosition
+    ) -> Result<Option<GameEvent>, ReplayError> {
+        let event = self.next_event(viewed_from)?;
+        if event.is_some() {
+            self.apply_next()?;
+        }
+
+        Ok(event)
+    }
+
+    /// Undo the last event. Returns false at the start of the game.
+    pub fn step_back(&mut self) -> Result<bool, ReplayError> {
+        if self.position == 0 {
+            return Ok(false);
+        }
+
+        self.seek(self.position - 1)?;
+        Ok(true)
+    }
+
+    /// Rebuild the state right before the event at the given index. The state is rebuilt from the
+    /// start of the game when going back.
+    pub fn seek(&mut self, position: usize) -> Result<(), ReplayError> {
+        let position = position.min(self.len());
+
+        if position < self.position {
+            self.state = self.replay.initial_game_state.clone();
+            self.position = 0;
+        }
+        while self.position < position {
+            self.apply_next()?;
+        }
+
+        Ok(())
+    }
+
+    /// Jump to the start of the turn.
+    pub fn seek_turn(&mut self, turn: u32) -> Result<(), ReplayError> {
+        self.seek_phase(turn, Phase::Beginning)
+    }
+
+    /// Jump to the start of the phase of the turn. Stays where it was if the game never reaches
+    /// the phase.
+    pub fn seek_phase(&mut self, turn: u32, phase: Phase) -> Result<(), ReplayError> {
+        let previous_position = self.position;
+        self.seek(0)?;
+
+        loop {
+            // A new turn is set while still in the last phase of the previous turn, so only the
+            // points right after a phase has started are counted.
+            let is_phase_start =
+                self.position == 0 ||
+                matches!(
+                    self.replay.events[self.position - 1],
+                    GameEvent::StateUpdated(UpdateGameState::SetPhase(_))
+                );
+            if is_phase_start && self.state.turn == turn && self.state.phase == phase {
+                return Ok(());
+            }
+
+            if self.is_at_end() {
+                self.seek(previous_position)?;
+                return Err(ReplayError::PointNotReached { turn, phase });
+            }
+            self.apply_next()?;
+        }
+    }
+
+    fn apply_next(&mut self) -> Result<(), ReplayError> {
+        if let GameEvent::StateUpdated(update) = self.replay.events[self.position] {
+            self.state
+                .apply_update(update)
+                .map_err(|error| ReplayError::InvalidUpdate { index: self.position, error })?;
+        }
+        self.position += 1;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::frames::{ PlayerId, PlayerIdentity };
+    use crate::replays::REPLAY_FORMAT_VERSION;
+    use crate::rules::deck_list::DeckList;
+    use crate::rules::megami::Megami;
+    use crate::rules::states::{ Petals, PetalsPosition, PlayerState, PlayersData };
+    use crate::rules::PlayerPos;
+
+    /// Turn 0 goes through its phases, then turn 1 starts and reaches its main phase.
+    fn replay() -> Replay {
+        let update = GameEvent::StateUpdated;
+        let identity = |id| PlayerIdentity { id: PlayerId(id), display_name: String::new() };
+        let deck_list = DeckList {
+            megamis: [Megami::Yurina, Megami::Himika],
+            normals: vec![],
+            specials: vec![],
+        };
+
+        Replay {
+            version: REPLAY_FORMAT_VERSION,
+            players: PlayersData::new(identity(0), identity(1)),
+            deck_lists: PlayersData::new(deck_list.clone(), deck_list),
+            seed: 0,
+            initial_game_state: GameState::new(
+                0,
+                PlayerPos::P1,
+                Phase::Beginning,
+                Petals::new(10, Some(10)),
+                Petals::new(0, None),
+                PlayersData::new(PlayerState::default(), PlayerState::default())
+            ),
+            events: vec![
+                update(UpdateGameState::SetPhase(Phase::Main)),
+                update(UpdateGameState::TransferPetals {
+                    from: PetalsPosition::Distance,
+                    to: PetalsPosition::Dust,
+                    amount: 1,
+                }),
+                update(UpdateGameState::SetPhase(Phase::End)),
+                update(UpdateGameState::SetTurn { turn: 1, turn_player: PlayerPos::P2 }),
+                update(UpdateGameState::SetPhase(Phase::Beginning)),
+                update(UpdateGameState::AddToVigor { player: PlayerPos::P2, diff: 1 }),
+                update(UpdateGameState::SetPhase(Phase::Main)),
+                update(UpdateGameState::TransferPetals {
+                    from: PetalsPosition::Aura(PlayerPos::P2),
+                    to: PetalsPosition::Dust,
+                    amount: 1,
+                }),
+            ],
+            decisions: vec![],
+        }
+    }
+
+    /// A player that has only ever moved forward to the position.
+    fn fresh_player_at(position: usize) -> ReplayPlayer {
+        let mut player = ReplayPlayer::new(replay());
+        player.seek(position).unwrap();
+        player
+    }
+
+    fn master_view(player: &ReplayPlayer) -> StateView {
+        player.state_view(ObservePosition::MasterView)
+    }
+
+    #[test]
+    fn seeking_back_rebuilds_the_state() {
+        let mut player = ReplayPlayer::new(replay());
+
+        player.seek(7).unwrap();
+        player.seek(3).unwrap();
+
+        assert_eq!(player.position(), 3);
+        assert_eq!(master_view(&player), master_view(&fresh_player_at(3)));
+    }
+
+    #[test]
+    fn seeking_past_the_end_stops_at_the_end() {
+        let mut player = ReplayPlayer::new(replay());
+
+        player.seek(100).unwrap();
+
+        assert!(player.is_at_end());
+        assert_eq!(player.position(), player.len());
+    }
+
+    #[test]
+    fn step_back_undoes_the_last_event() {
+        let mut player = fresh_player_at(6);
+
+        assert!(player.step_back().unwrap());
+
+        assert_eq!(player.position(), 5);
+        assert_eq!(master_view(&player), master_view(&fresh_player_at(5)));
+
+        player.seek(0).unwrap();
+        assert!(!player.step_back().unwrap());
+        assert_eq!(player.position(), 0);
+    }
+
+    #[test]
+    fn seek_phase_stops_right_after_the_phase_has_started() {
+        let mut player = fresh_player_at(8);
+
+        player.seek_phase(1, Phase::Beginning).unwrap();
+        assert_eq!(player.position(), 5);
+
+        player.seek_phase(1, Phase::Main).unwrap();
+        assert_eq!(player.position(), 7);
+
+        player.seek_turn(0).unwrap();
+        assert_eq!(player.position(), 0);
+    }
+
+    #[test]
+    fn unreached_phase_keeps_the_previous_position() {
+        let mut player = fresh_player_at(2);
+
+        let result = player.seek_phase(2, Phase::Main);
+
+        assert!(
+            matches!(result, Err(ReplayError::PointNotReached { turn: 2, phase: Phase::Main }))
+        );
+        assert_eq!(player.position(), 2);
+        assert_eq!(master_view(&player), master_view(&fresh_player_at(2)));
+    }
+}
@@ -1,11 +1,15 @@
 pub mod attack;
 pub mod cards;
 pub mod condition;
+pub mod deck_list;
 pub mod effects;
 pub mod events;
+pub mod megami;
 pub mod player_actions;
+pub mod relative_positions;
 pub mod states;
 
+use crate::rules::states::PlayersData;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Copy, Clone, Debug)]
@@ -37,8 +41,38 @@ pub enum ObservePosition {
     ByStander,
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub enum GameResult {
     Draw,
     Winner(PlayerPos),
 }
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum GameEndReason {
+    /// A player's life has reached zero.
+    LifeReachedZero,
+    /// The loser has surrendered.
+    Surrender,
+    /// The connection to the loser has been lost.
+    Disconnect,
+    /// The loser has run out of time.
+    Timeout,
+    /// The loser has kept requesting invalid actions.
+    InvalidActionForfeit,
+}
+
+/// Statistics of a finished game.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct GameSummary {
+    pub turns: u32,
+    /// Damage dealt to the opponent by each player, in petals actually taken from the aura and life.
+    pub damage_dealt: PlayersData<u32>,
+    pub cards_played: PlayersData<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct GameOutcome {
+    pub result: GameResult,
+    pub reason: GameEndReason,
+    pub summary: GameSummary,
+}
@@ -1,16 +1,56 @@
 use crate::rules::condition::Condition;
 use crate::rules::effects::Effect;
+use serde::{Deserialize, Serialize};
 
 pub type Damage = Option<u32>;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub struct AttackDamage {
     pub aura_damage: Damage,
     pub life_damage: Damage,
 }
 
+/// Where the defender takes the damage.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DamageTarget {
+    Aura,
+    Life,
+}
+
 #[derive(Debug, Copy, Clone)]
-pub enum DamageModifier {}
+pub enum DamageModifier {
+    /// Add to the aura damage. A "-" damage stays "-".
+    AddAura(i32),
+    /// Add to the life damage. A "-" damage stays "-".
+    AddLife(i32),
+    SetAura(Damage),
+    SetLife(Damage),
+}
+
+impl DamageModifier {
+    pub fn apply(self, damage: AttackDamage) -> AttackDamage {
+        let add = |d: Damage, diff: i32| d.map(|d| (d as i32 + diff).max(0) as u32);
+
+        match self {
+            DamageModifier::AddAura(diff) => AttackDamage {
+                aura_damage: add(damage.aura_damage, diff),
+                ..damage
+            },
+            DamageModifier::AddLife(diff) => AttackDamage {
+                life_damage: add(damage.life_damage, diff),
+                ..damage
+            },
+            DamageModifier::SetAura(aura_damage) => AttackDamage {
+                aura_damage,
+                ..damage
+            },
+            DamageModifier::SetLife(life_damage) => AttackDamage {
+                life_damage,
+                ..damage
+            },
+        }
+    }
+}
 
 #[derive(Debug, Copy, Clone)]
 pub struct Attack {
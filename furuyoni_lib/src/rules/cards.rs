@@ -1,11 +1,24 @@
 use crate::rules::attack::Attack;
+use crate::rules::condition::Condition;
+use crate::rules::effects::Effect;
+use crate::rules::megami::Megami;
 use crate::rules::PlayerPos;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use serde::{ Deserialize, Serialize };
 
+mod himika;
 mod yurina;
 
 pub type Cards = Vec<Card>;
 
+/// Shuffle the cards deterministically, so that the same seed always results in the same order,
+/// even with other versions of `rand`.
+pub fn shuffle_cards(cards: &mut Cards, seed: u64) {
+    cards.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub enum CardsPosition {
     Hand(PlayerPos),
@@ -14,6 +27,10 @@ pub enum CardsPosition {
     Enhancements(PlayerPos),
     Played(PlayerPos),
     Discards(PlayerPos),
+    /// Unused(face-down) special cards.
+    Specials(PlayerPos),
+    /// Used(face-up) special cards.
+    UsedSpecials(PlayerPos),
 }
 
 impl CardsPosition {
@@ -24,7 +41,9 @@ impl CardsPosition {
             | CardsPosition::Deck(p)
             | CardsPosition::Enhancements(p)
             | CardsPosition::Played(p)
-            | CardsPosition::Discards(p) => *p,
+            | CardsPosition::Discards(p)
+            | CardsPosition::Specials(p)
+            | CardsPosition::UsedSpecials(p) => *p,
         }
     }
 }
@@ -60,13 +79,35 @@ impl CardSelectorCase {
 pub enum Card {
     Slash,
     Brandish,
+    Bash,
+    Iai,
+    Footwork,
+    Pressure,
+    Spirit,
+    MoonShadowFall,
+    WaveStorm,
+    FloatingBoat,
+    Shoot,
+    RapidFire,
+    MagnumCannon,
 }
 
 impl Card {
-    fn data(&self) -> &'static CardData {
+    pub fn data(&self) -> &'static CardData {
         match &self {
             Card::Slash => &yurina::SLASH,
             Card::Brandish => &yurina::BRANDISH,
+            Card::Bash => &yurina::BASH,
+            Card::Iai => &yurina::IAI,
+            Card::Footwork => &yurina::FOOTWORK,
+            Card::Pressure => &yurina::PRESSURE,
+            Card::Spirit => &yurina::SPIRIT,
+            Card::MoonShadowFall => &yurina::MOON_SHADOW_FALL,
+            Card::WaveStorm => &yurina::WAVE_STORM,
+            Card::FloatingBoat => &yurina::FLOATING_BOAT,
+            Card::Shoot => &himika::SHOOT,
+            Card::RapidFire => &himika::RAPID_FIRE,
+            Card::MagnumCannon => &himika::MAGNUM_CANNON,
         }
     }
 }
@@ -75,11 +116,14 @@ pub enum CardType {
     Normal,
     Special {
         flare_cost: u32,
+        /// A used special card returns to unused when the condition holds for its owner.
+        recur: Option<Condition>,
     },
 }
 
 pub struct CardData {
     pub id_str: &'static str,
+    pub megami: Megami,
     pub card_type: CardType,
     pub card_sub_type: CardSubType,
     pub play_data: CardPlayData,
@@ -89,6 +133,15 @@ pub enum CardPlayData {
     AttackCard {
         attack: Attack,
     },
+    ActionCard {
+        effects: &'static [Effect],
+    },
+    /// A card that stays in the enhancements zone until its sakura tokens run out.
+    EnhancementCard {
+        /// The number of sakura tokens(납) put on the card when played.
+        charge: u32,
+        on_disenhance: &'static [Effect],
+    },
 }
 
 pub enum CardSubType {
@@ -0,0 +1,73 @@
+use super::*;
+use crate::rules::attack::{Attack, AttackDamage, DamageModifier};
+use crate::rules::condition::Condition;
+use crate::rules::effects::Effect;
+use crate::rules::megami::Megami;
+use crate::rules::relative_positions::{RelativePetalsPosition, RelativePlayer};
+
+pub const SHOOT: CardData = CardData {
+    id_str: "NA-01-himika-O-N1",
+    megami: Megami::Himika,
+    card_type: CardType::Normal,
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::AttackCard {
+        attack: Attack {
+            base_damage: AttackDamage {
+                aura_damage: Some(2),
+                life_damage: Some(1),
+            },
+            range: &[4, 5, 6, 7, 8, 9, 10],
+            after_attack: &[],
+            damage_modifiers: &[],
+        },
+    },
+};
+
+/// Rapid fire(연화): stronger when it's the third card or later played in the turn.
+const RAPID_FIRE_CONDITION: Condition = Condition::PlayedCardsThisTurn {
+    player: RelativePlayer::User,
+    at_least: 3,
+};
+
+pub const RAPID_FIRE: CardData = CardData {
+    id_str: "NA-02-himika-O-N2",
+    megami: Megami::Himika,
+    card_type: CardType::Normal,
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::AttackCard {
+        attack: Attack {
+            base_damage: AttackDamage {
+                aura_damage: Some(2),
+                life_damage: Some(1),
+            },
+            range: &[7, 8],
+            after_attack: &[],
+            damage_modifiers: &[
+                (RAPID_FIRE_CONDITION, DamageModifier::AddAura(1)),
+                (RAPID_FIRE_CONDITION, DamageModifier::AddLife(1)),
+            ],
+        },
+    },
+};
+
+pub const MAGNUM_CANNON: CardData = CardData {
+    id_str: "NA-03-himika-O-N3",
+    megami: Megami::Himika,
+    card_type: CardType::Normal,
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::AttackCard {
+        attack: Attack {
+            base_damage: AttackDamage {
+                aura_damage: Some(3),
+                life_damage: Some(2),
+            },
+            range: &[5, 6, 7, 8],
+            after_attack: &[Effect::TransferPetals {
+                from: RelativePetalsPosition::Life(RelativePlayer::User),
+                to: RelativePetalsPosition::Dust,
+                amount: 1,
+            }],
+            damage_modifiers: &[],
+        },
+    },
+};
@@ -1,8 +1,13 @@
 use super::*;
-use crate::rules::attack::{Attack, AttackDamage};
+use crate::rules::megami::Megami;
+use crate::rules::attack::{Attack, AttackDamage, DamageModifier};
+use crate::rules::condition::DESPERATION;
+use crate::rules::effects::{Effect, EffectOption};
+use crate::rules::relative_positions::{RelativePetalsPosition, RelativePlayer};
 
 pub const SLASH: CardData = CardData {
     id_str: "NA-01-yurina-O-N1",
+    megami: Megami::Yurina,
     card_type: CardType::Normal,
     card_sub_type: CardSubType::None,
     play_data: CardPlayData::AttackCard {
@@ -20,6 +25,7 @@ pub const SLASH: CardData = CardData {
 
 pub const BRANDISH: CardData = CardData {
     id_str: "NA-02-yurina-O-N2",
+    megami: Megami::Yurina,
     card_type: CardType::Normal,
     card_sub_type: CardSubType::None,
     play_data: CardPlayData::AttackCard {
@@ -34,3 +40,148 @@ pub const BRANDISH: CardData = CardData {
         },
     },
 };
+
+pub const BASH: CardData = CardData {
+    id_str: "NA-03-yurina-O-N3",
+    megami: Megami::Yurina,
+    card_type: CardType::Normal,
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::AttackCard {
+        attack: Attack {
+            base_damage: AttackDamage {
+                aura_damage: Some(2),
+                life_damage: Some(1),
+            },
+            range: &[1, 2],
+            after_attack: &[],
+            damage_modifiers: &[(DESPERATION, DamageModifier::AddAura(1))],
+        },
+    },
+};
+
+pub const IAI: CardData = CardData {
+    id_str: "NA-04-yurina-O-N4",
+    megami: Megami::Yurina,
+    card_type: CardType::Normal,
+    card_sub_type: CardSubType::Throughout,
+    play_data: CardPlayData::AttackCard {
+        attack: Attack {
+            base_damage: AttackDamage {
+                aura_damage: Some(3),
+                life_damage: Some(2),
+            },
+            range: &[4],
+            after_attack: &[],
+            damage_modifiers: &[(DESPERATION, DamageModifier::AddLife(1))],
+        },
+    },
+};
+
+pub const FOOTWORK: CardData = CardData {
+    id_str: "NA-05-yurina-O-N5",
+    megami: Megami::Yurina,
+    card_type: CardType::Normal,
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::ActionCard {
+        effects: &[Effect::Choice(&[
+            EffectOption {
+                description: "Advance",
+                effects: &[Effect::ChangeDistance(-1)],
+            },
+            EffectOption {
+                description: "Retreat",
+                effects: &[Effect::ChangeDistance(1)],
+            },
+        ])],
+    },
+};
+
+pub const PRESSURE: CardData = CardData {
+    id_str: "NA-06-yurina-O-N6",
+    megami: Megami::Yurina,
+    card_type: CardType::Normal,
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::EnhancementCard {
+        charge: 2,
+        on_disenhance: &[Effect::Damage {
+            player: RelativePlayer::Opponent,
+            damage: AttackDamage {
+                aura_damage: None,
+                life_damage: Some(1),
+            },
+        }],
+    },
+};
+
+pub const SPIRIT: CardData = CardData {
+    id_str: "NA-07-yurina-O-N7",
+    megami: Megami::Yurina,
+    card_type: CardType::Normal,
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::ActionCard {
+        effects: &[Effect::TransferPetals {
+            from: RelativePetalsPosition::Dust,
+            to: RelativePetalsPosition::Flare(RelativePlayer::User),
+            amount: 1,
+        }],
+    },
+};
+
+pub const MOON_SHADOW_FALL: CardData = CardData {
+    id_str: "NA-S1-yurina-O-S1",
+    megami: Megami::Yurina,
+    card_type: CardType::Special {
+        flare_cost: 7,
+        recur: None,
+    },
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::AttackCard {
+        attack: Attack {
+            base_damage: AttackDamage {
+                aura_damage: Some(4),
+                life_damage: Some(4),
+            },
+            range: &[3, 4],
+            after_attack: &[],
+            damage_modifiers: &[],
+        },
+    },
+};
+
+pub const WAVE_STORM: CardData = CardData {
+    id_str: "NA-S2-yurina-O-S2",
+    megami: Megami::Yurina,
+    card_type: CardType::Special {
+        flare_cost: 3,
+        recur: None,
+    },
+    card_sub_type: CardSubType::Reaction,
+    play_data: CardPlayData::AttackCard {
+        attack: Attack {
+            base_damage: AttackDamage {
+                aura_damage: Some(2),
+                life_damage: None,
+            },
+            range: &[1, 2, 3, 4],
+            after_attack: &[Effect::ModifyReactedAttack(DamageModifier::AddAura(-2))],
+            damage_modifiers: &[],
+        },
+    },
+};
+
+pub const FLOATING_BOAT: CardData = CardData {
+    id_str: "NA-S3-yurina-O-S3",
+    megami: Megami::Yurina,
+    card_type: CardType::Special {
+        flare_cost: 2,
+        recur: None,
+    },
+    card_sub_type: CardSubType::None,
+    play_data: CardPlayData::ActionCard {
+        effects: &[Effect::TransferPetals {
+            from: RelativePetalsPosition::Dust,
+            to: RelativePetalsPosition::Aura(RelativePlayer::User),
+            amount: 5,
+        }],
+    },
+};
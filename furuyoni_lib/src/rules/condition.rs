@@ -1,3 +1,40 @@
+use crate::rules::relative_positions::{RelativePetalsPosition, RelativePlayer};
+
+/// A predicate on the game state, evaluated from the perspective of a card's user.
 #[derive(Debug, Copy, Clone)]
+pub enum Condition {
+    Not(&'static Condition),
+    All(&'static [Condition]),
+    Any(&'static [Condition]),
+    DistanceAtMost(u32),
+    DistanceAtLeast(u32),
+    PetalsAtMost {
+        position: RelativePetalsPosition,
+        count: u32,
+    },
+    PetalsAtLeast {
+        position: RelativePetalsPosition,
+        count: u32,
+    },
+    VigorAtLeast {
+        player: RelativePlayer,
+        vigor: i32,
+    },
+    HandAtLeast {
+        player: RelativePlayer,
+        count: u32,
+    },
+    /// The player has played at least the given number of cards in this turn.
+    PlayedCardsThisTurn {
+        player: RelativePlayer,
+        at_least: u32,
+    },
+    /// The card is being played as a reaction to an attack.
+    IsReacting,
+}
 
-pub enum Condition {}
+/// 決死: The user's life is 3 or lower.
+pub const DESPERATION: Condition = Condition::PetalsAtMost {
+    position: RelativePetalsPosition::Life(RelativePlayer::User),
+    count: 3,
+};
@@ -0,0 +1,131 @@
+use crate::rules::cards::{ Card, CardType };
+use crate::rules::megami::Megami;
+use serde::{ Deserialize, Serialize };
+use thiserror::Error;
+
+pub const NORMAL_CARDS_COUNT: usize = 7;
+pub const SPECIAL_CARDS_COUNT: usize = 3;
+
+/// The cards a player brings to a game: 7 normal cards and 3 special cards chosen from the cards of
+/// two goddesses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeckList {
+    pub megamis: [Megami; 2],
+    pub normals: Vec<Card>,
+    pub specials: Vec<Card>,
+}
+
+#[derive(Error, Debug, Copy, Clone, PartialEq)]
+pub enum InvalidDeckListError {
+    #[error("The same goddess has been chosen twice.")]
+    DuplicateMegami,
+    #[error("The deck should have {NORMAL_CARDS_COUNT} normal cards, but has {0}.")]
+    NormalCardsCount(usize),
+    #[error("The deck should have {SPECIAL_CARDS_COUNT} special cards, but has {0}.")]
+    SpecialCardsCount(usize),
+    #[error("The card {0:?} is in the wrong place: normal and special cards should be separated.")]
+    WrongCardType(Card),
+    #[error("The card {0:?} is in the deck more than once.")]
+    DuplicateCard(Card),
+    #[error("The card {0:?} doesn't belong to the chosen goddesses.")]
+    MegamiNotChosen(Card),
+}
+
+impl DeckList {
+    pub fn validate(&self) -> Result<(), InvalidDeckListError> {
+        if self.megamis[0] == self.megamis[1] {
+            return Err(InvalidDeckListError::DuplicateMegami);
+        }
+        if self.normals.len() != NORMAL_CARDS_COUNT {
+            return Err(InvalidDeckListError::NormalCardsCount(self.normals.len()));
+        }
+        if self.specials.len() != SPECIAL_CARDS_COUNT {
+            return Err(InvalidDeckListError::SpecialCardsCount(self.specials.len()));
+        }
+
+        let normals = self.normals.iter().map(|card| (card, false));
+        let specials = self.specials.iter().map(|card| (card, true));
+        let cards: Vec<_> = normals.chain(specials).collect();
+
+        for (i, &(card, should_be_special)) in cards.iter().enumerate() {
+            let data = card.data();
+
+            let is_special = matches!(data.card_type, CardType::Special { .. });
+            if is_special != should_be_special {
+                return Err(InvalidDeckListError::WrongCardType(*card));
+            }
+            if cards[..i].iter().any(|(other, _)| other == &card) {
+                return Err(InvalidDeckListError::DuplicateCard(*card));
+            }
+            if !self.megamis.contains(&data.megami) {
+                return Err(InvalidDeckListError::MegamiNotChosen(*card));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deck_list() -> DeckList {
+        DeckList {
+            megamis: [Megami::Yurina, Megami::Himika],
+            normals: vec![
+                Card::Slash,
+                Card::Brandish,
+                Card::Bash,
+                Card::Iai,
+                Card::Footwork,
+                Card::Shoot,
+                Card::RapidFire
+            ],
+            specials: vec![Card::MoonShadowFall, Card::WaveStorm, Card::FloatingBoat],
+        }
+    }
+
+    #[test]
+    fn deck_of_the_chosen_goddesses_is_valid() {
+        assert_eq!(deck_list().validate(), Ok(()));
+    }
+
+    #[test]
+    fn goddess_chosen_twice_is_refused() {
+        let deck_list = DeckList { megamis: [Megami::Yurina, Megami::Yurina], ..deck_list() };
+
+        assert_eq!(deck_list.validate(), Err(InvalidDeckListError::DuplicateMegami));
+    }
+
+    #[test]
+    fn wrong_number_of_cards_is_refused() {
+        let mut few_normals = deck_list();
+        few_normals.normals.pop();
+        assert_eq!(few_normals.validate(), Err(InvalidDeckListError::NormalCardsCount(6)));
+
+        let mut few_specials = deck_list();
+        few_specials.specials.pop();
+        assert_eq!(few_specials.validate(), Err(InvalidDeckListError::SpecialCardsCount(2)));
+    }
+
+    #[test]
+    fn special_card_among_the_normal_cards_is_refused() {
+        let mut deck_list = deck_list();
+        deck_list.normals[0] = Card::WaveStorm;
+        deck_list.specials[1] = Card::Slash;
+
+        assert_eq!(
+            deck_list.validate(),
+            Err(InvalidDeckListError::WrongCardType(Card::WaveStorm))
+        );
+    }
+
+    #[test]
+    fn card_taken_twice_is_refused() {
+        let mut deck_list = deck_list();
+        deck_list.normals[1] = Card::Slash;
+
+        assert_eq!(deck_list.validate(), Err(InvalidDeckListError::DuplicateCard(Card::Slash)));
+    }
+}
@@ -1,2 +1,51 @@
+use crate::rules::attack::{AttackDamage, DamageModifier};
+use crate::rules::condition::Condition;
+use crate::rules::relative_positions::{RelativePetalsPosition, RelativePlayer};
+
+/// A single step of a card's ability, described from the perspective of the card's user.
 #[derive(Debug, Copy, Clone)]
-pub enum Effect {}
+pub enum Effect {
+    /// Move as many petals as possible, up to the given amount.
+    TransferPetals {
+        from: RelativePetalsPosition,
+        to: RelativePetalsPosition,
+        amount: u32,
+    },
+    Draw {
+        player: RelativePlayer,
+        amount: u32,
+    },
+    /// The player discards(伏せ) the given number of cards of their choice from the hand.
+    Discard {
+        player: RelativePlayer,
+        amount: u32,
+    },
+    /// Move petals between the distance and the dust. Positive values increase the distance.
+    ChangeDistance(i32),
+    AddToVigor {
+        player: RelativePlayer,
+        diff: i32,
+    },
+    /// A damage that is not an attack.
+    Damage {
+        player: RelativePlayer,
+        damage: AttackDamage,
+    },
+    /// The user chooses one of the options.
+    Choice(&'static [EffectOption]),
+    If {
+        condition: Condition,
+        then: &'static [Effect],
+        otherwise: &'static [Effect],
+    },
+    /// Cancel the attack being reacted to.
+    CancelReactedAttack,
+    /// Modify the damage of the attack being reacted to.
+    ModifyReactedAttack(DamageModifier),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct EffectOption {
+    pub description: &'static str,
+    pub effects: &'static [Effect],
+}
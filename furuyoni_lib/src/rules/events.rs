@@ -1,13 +1,14 @@
+use crate::rules::attack::{ AttackDamage, DamageTarget };
 use crate::rules::cards::{ Card, CardsPosition };
 use crate::rules::player_actions::BasicAction;
 use crate::rules::states::PetalsPosition;
 use crate::rules::states::Phase;
-use crate::rules::{ GameResult, PlayerPos };
+use crate::rules::{ GameOutcome, PlayerPos };
 use serde::{ Deserialize, Serialize };
 
 use super::cards::CardSelector;
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub enum UpdateGameState {
     SetTurn {
         turn: u32,
@@ -32,17 +33,59 @@ pub enum UpdateGameState {
         to: CardSelector,
         card: Option<Card>,
     },
+    /// The seed is only given to the observers who can see the deck.
+    ShuffleDeck {
+        player: PlayerPos,
+        seed: Option<u64>,
+    },
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub enum GameEvent {
     StateUpdated(UpdateGameState),
     PerformBasicAction {
         player: PlayerPos,
         action: BasicAction,
     },
-    GameEnd {
-        result: GameResult,
-    }, // Todo: card play events, etc...
+    PlayCard {
+        player: PlayerPos,
+        card: Card,
+    },
+    Attack(AttackEvent),
+    TakeDamage {
+        player: PlayerPos,
+        target: DamageTarget,
+        amount: u32,
+    },
+    /// An enhancement card ran out of its sakura tokens and its on-disenhance(파기시) effects are
+    /// about to be resolved.
+    Disenhance {
+        player: PlayerPos,
+        card: Card,
+    },
+    /// The player is putting the played pile and the discard pile back to the deck.
+    ReshuffleDeck {
+        player: PlayerPos,
+    },
+    GameEnd(GameOutcome), // Todo: more card play events, etc...
     // Todo: 메인페이즈 BasicAction(cost 지불 + performBasicAction)을 따로 넣을까. 굳이? 나중에 필요하면.
 }
+
+/// Events emitted for each step of an attack resolution.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum AttackEvent {
+    Declared {
+        attacker: PlayerPos,
+        base_damage: AttackDamage,
+    },
+    /// The attack has been cancelled by a reaction. The attack is not resolved further.
+    Cancelled,
+    /// The distance was out of the attack's range. The attack is not resolved further.
+    OutOfRange,
+    /// The damage after applying the damage modifiers.
+    DamageDetermined {
+        damage: AttackDamage,
+    },
+    AfterAttack,
+    Resolved,
+}
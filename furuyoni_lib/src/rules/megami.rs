@@ -0,0 +1,8 @@
+use serde::{ Deserialize, Serialize };
+
+/// The goddesses(메가미). Each player chooses two of them and builds a deck from their cards.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Megami {
+    Yurina,
+    Himika,
+}
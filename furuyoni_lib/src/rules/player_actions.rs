@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
 pub struct HandSelector(pub usize);
 
+/// Selects a card among the unused special cards.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
+pub struct SpecialSelector(pub usize);
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq, Hash)]
 pub enum BasicAction {
     MoveForward,
@@ -14,6 +18,7 @@ pub enum BasicAction {
 #[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
 pub enum PlayableCardSelector {
     Hand(HandSelector),
+    Special(SpecialSelector),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
@@ -31,3 +36,9 @@ pub enum MainPhaseAction {
     PlayCard(PlayableCardSelector),
     EndMainPhase,
 }
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum ReactionAction {
+    PlayReaction(PlayableCardSelector),
+    Pass,
+}
@@ -0,0 +1,39 @@
+use crate::rules::states::PetalsPosition;
+use crate::rules::PlayerPos;
+
+/// A player relative to the user of a card.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RelativePlayer {
+    User,
+    Opponent,
+}
+
+impl RelativePlayer {
+    pub fn into_absolute(self, user: PlayerPos) -> PlayerPos {
+        match self {
+            RelativePlayer::User => user,
+            RelativePlayer::Opponent => user.other(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RelativePetalsPosition {
+    Distance,
+    Dust,
+    Aura(RelativePlayer),
+    Flare(RelativePlayer),
+    Life(RelativePlayer),
+}
+
+impl RelativePetalsPosition {
+    pub fn into_absolute(self, user: PlayerPos) -> PetalsPosition {
+        match self {
+            RelativePetalsPosition::Distance => PetalsPosition::Distance,
+            RelativePetalsPosition::Dust => PetalsPosition::Dust,
+            RelativePetalsPosition::Aura(p) => PetalsPosition::Aura(p.into_absolute(user)),
+            RelativePetalsPosition::Flare(p) => PetalsPosition::Flare(p.into_absolute(user)),
+            RelativePetalsPosition::Life(p) => PetalsPosition::Life(p.into_absolute(user)),
+        }
+    }
+}
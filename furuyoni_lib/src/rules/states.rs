@@ -1,10 +1,16 @@
 use serde::{Deserialize, Serialize};
 
+mod filters;
+mod game_state;
 mod petals;
+mod player_state;
 mod players_data;
 mod state_view;
 
+pub use filters::*;
+pub use game_state::*;
 pub use petals::*;
+pub use player_state::*;
 pub use players_data::PlayersData;
 pub use state_view::*;
 
@@ -0,0 +1,99 @@
+use crate::rules::cards::CardsPosition;
+use crate::rules::events::{ GameEvent, UpdateGameState };
+use crate::rules::states::{ GameState, PlayerStateViews, PlayersData, StateView };
+use crate::rules::{ ObservePosition, PlayerPos };
+use thiserror::Error;
+
+/// Return the state as seen from the given position.
+pub fn filter_state(viewed_from: ObservePosition, state: &GameState) -> StateView {
+    let player_states = &state.player_states;
+
+    StateView {
+        turn_player: state.turn_player,
+        phase: state.phase,
+        turn: state.turn,
+        distance: state.distance.clone(),
+        dust: state.dust.clone(),
+        player_states: PlayerStateViews::new(
+            player_states[PlayerPos::P1].as_viewed_from(PlayerPos::P1, viewed_from),
+            player_states[PlayerPos::P2].as_viewed_from(PlayerPos::P2, viewed_from)
+        ),
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to filter an event: {0:?}")]
+pub struct EventFilterError(pub GameEvent);
+
+/// Hide the information in the event that can't be seen from the given position. `state` should be
+/// the state before the event.
+pub fn filter_event(
+    state: &GameState,
+    position: ObservePosition,
+    event: GameEvent
+) -> Result<GameEvent, EventFilterError> /* Todo: to Result<Option<GameEvent>, ?> */ {
+    let (can_view_p1, can_view_p2, can_view_master) = {
+        match position {
+            ObservePosition::RelativeTo(p) => (p == PlayerPos::P1, p == PlayerPos::P2, false),
+            ObservePosition::MasterView => (true, true, true),
+            ObservePosition::ByStander => (false, false, false),
+        }
+    };
+    let can_view_p = PlayersData::new(can_view_p1, can_view_p2);
+
+    let e = match event {
+        e @ GameEvent::StateUpdated(update) =>
+            GameEvent::StateUpdated(match update {
+                | u @ UpdateGameState::SetTurn { .. }
+                | u @ UpdateGameState::SetPhase(_)
+                | u @ UpdateGameState::TransferPetals { .. }
+                | u @ UpdateGameState::AddToVigor { .. } => u,
+                u @ UpdateGameState::TransferCard { from, to } => {
+                    let is_from_open = is_open(can_view_master, &can_view_p, &from.position);
+                    let is_to_open = is_open(can_view_master, &can_view_p, &to.position);
+
+                    if is_from_open {
+                        u
+                    } else {
+                        UpdateGameState::TransferCardFromHidden {
+                            from: from.position,
+                            to,
+                            card: if is_to_open {
+                                Some(state.select_card(from).ok_or(EventFilterError(e))?)
+                            } else {
+                                None
+                            },
+                        }
+                    }
+                }
+
+                UpdateGameState::TransferCardFromHidden { .. } => {
+                    panic!();
+                }
+                UpdateGameState::ShuffleDeck { player, seed } =>
+                    UpdateGameState::ShuffleDeck {
+                        player,
+                        seed: seed.filter(|_| can_view_master),
+                    },
+            }),
+        e => e,
+    };
+
+    Ok(e)
+}
+
+fn is_open(
+    can_view_master: bool,
+    can_view_p: &PlayersData<bool>,
+    position: &CardsPosition
+) -> bool {
+    match position {
+        CardsPosition::Deck(_) => can_view_master,
+        CardsPosition::Discards(p) | CardsPosition::Hand(p) | CardsPosition::Specials(p) =>
+            can_view_p[p],
+        | CardsPosition::Playing(_)
+        | CardsPosition::Enhancements(_)
+        | CardsPosition::Played(_)
+        | CardsPosition::UsedSpecials(_) => true,
+    }
+}
@@ -1,15 +1,18 @@
-use crate::game::states::player_state::PlayerState;
-use furuyoni_lib::rules::cards::{Card, CardSelector, CardSelectorCase, Cards, CardsPosition};
-use furuyoni_lib::rules::events::UpdateGameState;
-use furuyoni_lib::rules::states::{Petals, PetalsPosition, Phase, PlayersData};
-use furuyoni_lib::rules::PlayerPos;
+use crate::rules::cards::{
+    shuffle_cards, Card, CardSelector, CardSelectorCase, Cards, CardsPosition,
+};
+use crate::rules::events::UpdateGameState;
+use crate::rules::states::player_state::PlayerState;
+use crate::rules::states::{Petals, PetalsPosition, Phase, PlayersData};
+use crate::rules::PlayerPos;
+use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 use thiserror::Error;
 
-pub(crate) type PlayerStates = PlayersData<PlayerState>;
+pub type PlayerStates = PlayersData<PlayerState>;
 
-#[derive(Debug, Clone)]
-pub(crate) struct GameStateInner {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameStateInner {
     pub turn: u32,
     pub turn_player: PlayerPos,
     pub phase: Phase,
@@ -18,13 +21,13 @@ pub(crate) struct GameStateInner {
     pub player_states: PlayerStates,
 }
 
-#[derive(Debug, Clone)]
-pub(crate) struct GameState {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameState {
     inner: GameStateInner,
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum InvalidGameUpdateError {
+pub enum InvalidGameUpdateError {
     #[error("The given card selector's index was over the size of the cards")]
     CardSelectorOutOfBounds,
     #[error("Vigor has been pushed to go below 0 or above 2.")]
@@ -35,6 +38,10 @@ pub(crate) enum InvalidGameUpdateError {
     InvalidPetalTransfer,
     #[error("An update only for state views have been requested.")]
     UpdateOnlyForView,
+    #[error("A card with sakura tokens on it has been moved.")]
+    TokensLeftOnCard,
+    #[error("A deck shuffle without the seed has been requested.")]
+    MissingShuffleSeed,
 }
 
 impl GameStateInner {
@@ -45,6 +52,9 @@ impl GameStateInner {
             PetalsPosition::Aura(player) => &mut self.player_states[player].aura,
             PetalsPosition::Flare(player) => &mut self.player_states[player].flare,
             PetalsPosition::Life(player) => &mut self.player_states[player].life,
+            PetalsPosition::Enhancement { player, index } => {
+                &mut self.player_states[player].enhancement_tokens[index]
+            }
         }
     }
 
@@ -55,28 +65,44 @@ impl GameStateInner {
             PetalsPosition::Aura(player) => &self.player_states[player].aura,
             PetalsPosition::Flare(player) => &self.player_states[player].flare,
             PetalsPosition::Life(player) => &self.player_states[player].life,
+            PetalsPosition::Enhancement { player, index } => {
+                &self.player_states[player].enhancement_tokens[index]
+            }
+        }
+    }
+
+    pub fn has_petals(&self, petal_position: PetalsPosition) -> bool {
+        match petal_position {
+            PetalsPosition::Enhancement { player, index } => {
+                index < self.player_states[player].enhancement_tokens.len()
+            }
+            _ => true,
         }
     }
 
     fn cards_mut(&mut self, cards_position: CardsPosition) -> &mut Cards {
         match cards_position {
             CardsPosition::Hand(p) => &mut self.player_states[p].hand,
-            CardsPosition::Playing(p) => &mut self.player_states[p].hand,
+            CardsPosition::Playing(p) => &mut self.player_states[p].playing,
             CardsPosition::Deck(p) => &mut self.player_states[p].deck,
             CardsPosition::Enhancements(p) => &mut self.player_states[p].enhancements,
             CardsPosition::Played(p) => &mut self.player_states[p].played_pile,
             CardsPosition::Discards(p) => &mut self.player_states[p].discard_pile,
+            CardsPosition::Specials(p) => &mut self.player_states[p].specials,
+            CardsPosition::UsedSpecials(p) => &mut self.player_states[p].used_specials,
         }
     }
 
     pub fn cards(&self, cards_position: CardsPosition) -> &Cards {
         match cards_position {
             CardsPosition::Hand(p) => &self.player_states[p].hand,
-            CardsPosition::Playing(p) => &self.player_states[p].hand,
+            CardsPosition::Playing(p) => &self.player_states[p].playing,
             CardsPosition::Deck(p) => &self.player_states[p].deck,
             CardsPosition::Enhancements(p) => &self.player_states[p].enhancements,
             CardsPosition::Played(p) => &self.player_states[p].played_pile,
             CardsPosition::Discards(p) => &self.player_states[p].discard_pile,
+            CardsPosition::Specials(p) => &self.player_states[p].specials,
+            CardsPosition::UsedSpecials(p) => &self.player_states[p].used_specials,
         }
     }
 
@@ -118,6 +144,10 @@ impl GameState {
 
         match update {
             UpdateGameState::TransferPetals { from, to, amount } => {
+                if !state.has_petals(from) || !state.has_petals(to) {
+                    return Err(InvalidGameUpdateError::InvalidPetalTransfer);
+                }
+
                 let from_petals = state.petals_mut(from);
                 let from_new = from_petals
                     .count
@@ -150,14 +180,23 @@ impl GameState {
                 state.phase = phase;
             }
             UpdateGameState::TransferCard { from, to } => {
-                let cards_from = self.inner.cards_mut(from.position);
+                let len_from = self.inner.cards(from.position).len();
 
-                let index_from = from.case.index(cards_from.len());
-                if index_from >= cards_from.len() {
+                let index_from = from.case.index(len_from);
+                if index_from >= len_from {
                     return Err(InvalidGameUpdateError::CardSelectorOutOfBounds);
                 }
 
-                let taken = cards_from.remove(index_from);
+                if let CardsPosition::Enhancements(p) = from.position
+                    && self.inner.player_states[p].enhancement_tokens[index_from].count != 0
+                {
+                    return Err(InvalidGameUpdateError::TokensLeftOnCard);
+                }
+
+                let taken = self.inner.cards_mut(from.position).remove(index_from);
+                if let CardsPosition::Enhancements(p) = from.position {
+                    self.inner.player_states[p].enhancement_tokens.remove(index_from);
+                }
 
                 let cards_to = self.inner.cards_mut(to.position);
                 let index_to = to.case.index(cards_to.len());
@@ -166,10 +205,19 @@ impl GameState {
                     return Err(InvalidGameUpdateError::CardSelectorOutOfBounds);
                 }
                 cards_to.insert(index_to, taken);
+                if let CardsPosition::Enhancements(p) = to.position {
+                    self.inner.player_states[p]
+                        .enhancement_tokens
+                        .insert(index_to, Petals::new(0, None));
+                }
             }
             UpdateGameState::TransferCardFromHidden { .. } => {
                 return Err(InvalidGameUpdateError::UpdateOnlyForView)
             }
+            UpdateGameState::ShuffleDeck { player, seed } => {
+                let seed = seed.ok_or(InvalidGameUpdateError::MissingShuffleSeed)?;
+                shuffle_cards(&mut state.player_states[player].deck, seed);
+            }
         }
 
         Ok(())
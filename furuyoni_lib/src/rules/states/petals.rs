@@ -22,5 +22,9 @@ pub enum PetalsPosition {
     Aura(PlayerPos),
     Flare(PlayerPos),
     Life(PlayerPos),
-    // Todo: 부여패.
+    /// Sakura tokens on an enhancement card(부여패).
+    Enhancement {
+        player: PlayerPos,
+        index: usize,
+    },
 }
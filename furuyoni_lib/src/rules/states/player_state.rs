@@ -1,17 +1,25 @@
-use crate::game::Vigor;
-use furuyoni_lib::rules::cards::Cards;
-use furuyoni_lib::rules::states::Petals;
-use furuyoni_lib::rules::states::{CardsView, PlayerStateView};
-use furuyoni_lib::rules::{ObservePosition, PlayerPos};
+use crate::rules::cards::Cards;
+use crate::rules::deck_list::DeckList;
+use crate::rules::states::Petals;
+use crate::rules::states::{CardsView, PlayerStateView};
+use crate::rules::{ObservePosition, PlayerPos};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
-pub(crate) struct PlayerState {
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct Vigor(pub i32);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlayerState {
     pub hand: Cards,
     pub deck: Cards,
     pub enhancements: Cards,
+    /// Sakura tokens on each enhancement card. Has the same length with `enhancements`.
+    pub enhancement_tokens: Vec<Petals>,
     pub playing: Cards,
     pub played_pile: Cards,
     pub discard_pile: Cards,
+    pub specials: Cards,
+    pub used_specials: Cards,
 
     pub vigor: Vigor,
     pub aura: Petals,
@@ -25,9 +33,12 @@ impl Default for PlayerState {
             hand: vec![],
             deck: Vec::default(),
             enhancements: vec![],
+            enhancement_tokens: vec![],
             playing: vec![],
             played_pile: vec![],
             discard_pile: vec![],
+            specials: vec![],
+            used_specials: vec![],
             vigor: Vigor(0),
             aura: Petals::new(3, Some(5)),
             life: Petals::new(10, Some(10)),
@@ -37,6 +48,14 @@ impl Default for PlayerState {
 }
 
 impl PlayerState {
+    pub fn from_deck_list(deck_list: &DeckList) -> Self {
+        Self {
+            deck: deck_list.normals.clone(),
+            specials: deck_list.specials.clone(),
+            ..Default::default()
+        }
+    }
+
     pub fn as_viewed_from(
         &self,
         owner: PlayerPos,
@@ -54,9 +73,12 @@ impl PlayerState {
             hand: CardsView::from(&self.hand, can_view_personals),
             deck: CardsView::from(&self.deck.clone(), can_view_all),
             enhancements: self.enhancements.clone(),
+            enhancement_tokens: self.enhancement_tokens.clone(),
             playing: self.playing.clone(),
             played_pile: self.played_pile.clone(),
             discard_pile: CardsView::from(&self.discard_pile, can_view_personals),
+            specials: CardsView::from(&self.specials, can_view_personals),
+            used_specials: self.used_specials.clone(),
             vigor: self.vigor.0,
             aura: self.aura.clone(),
             life: self.life.clone(),
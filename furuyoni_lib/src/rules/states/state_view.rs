@@ -1,4 +1,4 @@
-use crate::rules::cards::{ Card, Cards, CardsPosition };
+use crate::rules::cards::{ shuffle_cards, Card, Cards, CardsPosition };
 use crate::rules::events::UpdateGameState;
 use crate::rules::states::petals::Petals;
 use crate::rules::states::players_data::PlayersData;
@@ -118,8 +118,12 @@ pub struct PlayerStateView {
     pub deck: CardsView,
     pub playing: Cards,
     pub enhancements: Cards,
+    /// Sakura tokens on each enhancement card. Has the same length with `enhancements`.
+    pub enhancement_tokens: Vec<Petals>,
     pub played_pile: Cards,
     pub discard_pile: CardsView,
+    pub specials: CardsView,
+    pub used_specials: Cards,
 
     pub vigor: i32,
     pub aura: Petals,
@@ -151,6 +155,8 @@ pub enum InvalidGameViewUpdateError {
     InvalidPetalTransfer,
     #[error("The update and state's visibility(hidden/open) didn't match.")]
     VisibilityMismatch,
+    #[error("A card with sakura tokens on it has been moved.")]
+    TokensLeftOnCard,
 }
 
 impl StateView {
@@ -161,6 +167,9 @@ impl StateView {
             PetalsPosition::Aura(player) => &self.player_states[player].aura,
             PetalsPosition::Flare(player) => &self.player_states[player].flare,
             PetalsPosition::Life(player) => &self.player_states[player].life,
+            PetalsPosition::Enhancement { player, index } => {
+                &self.player_states[player].enhancement_tokens[index]
+            }
         }
     }
 
@@ -171,9 +180,45 @@ impl StateView {
             PetalsPosition::Aura(player) => &mut self.player_states[player].aura,
             PetalsPosition::Flare(player) => &mut self.player_states[player].flare,
             PetalsPosition::Life(player) => &mut self.player_states[player].life,
+            PetalsPosition::Enhancement { player, index } => {
+                &mut self.player_states[player].enhancement_tokens[index]
+            }
+        }
+    }
+
+    fn has_petals(&self, petal_position: PetalsPosition) -> bool {
+        match petal_position {
+            PetalsPosition::Enhancement { player, index } => {
+                index < self.player_states[player].enhancement_tokens.len()
+            }
+            _ => true,
+        }
+    }
+
+    /// Keep the enhancement tokens in sync with the enhancement cards.
+    fn on_card_inserted(&mut self, position: CardsPosition, index: usize) {
+        if let CardsPosition::Enhancements(p) = position {
+            self.player_states[p]
+                .enhancement_tokens
+                .insert(index, Petals::new(0, None));
         }
     }
 
+    /// Keep the enhancement tokens in sync with the enhancement cards.
+    fn on_card_removed(
+        &mut self,
+        position: CardsPosition,
+        index: usize,
+    ) -> Result<(), InvalidGameViewUpdateError> {
+        if let CardsPosition::Enhancements(p) = position {
+            let tokens = self.player_states[p].enhancement_tokens.remove(index);
+            if tokens.count != 0 {
+                return Err(InvalidGameViewUpdateError::TokensLeftOnCard);
+            }
+        }
+        Ok(())
+    }
+
     pub fn cards_view(&self, cards_position: CardsPosition) -> CardsViewRef {
         match cards_position {
             CardsPosition::Hand(p) => self.player_states[p].hand.get_ref(),
@@ -182,6 +227,8 @@ impl StateView {
             CardsPosition::Enhancements(p) => (&self.player_states[p].enhancements).into(),
             CardsPosition::Played(p) => (&self.player_states[p].played_pile).into(),
             CardsPosition::Discards(p) => self.player_states[p].discard_pile.get_ref(),
+            CardsPosition::Specials(p) => self.player_states[p].specials.get_ref(),
+            CardsPosition::UsedSpecials(p) => (&self.player_states[p].used_specials).into(),
         }
     }
 
@@ -193,6 +240,8 @@ impl StateView {
             CardsPosition::Enhancements(p) => (&mut self.player_states[p].enhancements).into(),
             CardsPosition::Played(p) => (&mut self.player_states[p].played_pile).into(),
             CardsPosition::Discards(p) => self.player_states[p].discard_pile.get_ref_mut(),
+            CardsPosition::Specials(p) => self.player_states[p].specials.get_ref_mut(),
+            CardsPosition::UsedSpecials(p) => (&mut self.player_states[p].used_specials).into(),
         }
     }
 
@@ -203,6 +252,10 @@ impl StateView {
     ) -> Result<(), InvalidGameViewUpdateError> {
         match update {
             UpdateGameState::TransferPetals { from, to, amount } => {
+                if !self.has_petals(from) || !self.has_petals(to) {
+                    return Err(InvalidGameViewUpdateError::InvalidPetalTransfer);
+                }
+
                 let from_petals = self.petals_mut(from);
                 from_petals.count = from_petals.count
                     .checked_sub(amount)
@@ -234,11 +287,13 @@ impl StateView {
                     return Err(InvalidGameViewUpdateError::CardSelectorOutOfBounds);
                 }
                 let taken = from_cards.remove(from_index);
+                self.on_card_removed(from.position, from_index)?;
 
                 let mut to_cards = self.cards_view_mut(to.position);
                 let to_index = to.case.index(to_cards.len());
 
                 to_cards.insert_card(to_index, taken)?;
+                self.on_card_inserted(to.position, to_index);
             }
             UpdateGameState::TransferCardFromHidden { from, to, card } => {
                 let cards_from_len = match self.cards_view_mut(from) {
@@ -254,7 +309,9 @@ impl StateView {
                     mut cards_to @ CardsViewMutRef::Open { .. } => {
                         let card = card.ok_or(InvalidGameViewUpdateError::VisibilityMismatch)?;
                         // FIXME: weird code structure (we do the same match in 'insert_card'). Should have a separate function that handles only the 'Open' case?
-                        cards_to.insert_card(to.case.index(cards_to.len()), card)?;
+                        let to_index = to.case.index(cards_to.len());
+                        cards_to.insert_card(to_index, card)?;
+                        self.on_card_inserted(to.position, to_index);
                     }
                     CardsViewMutRef::Hidden { length } => {
                         if !card.is_none() {
@@ -264,6 +321,15 @@ impl StateView {
                     }
                 }
             }
+            UpdateGameState::ShuffleDeck { player, seed } => {
+                match (&mut self.player_states[player].deck, seed) {
+                    (CardsView::Open { cards }, Some(seed)) => shuffle_cards(cards, seed),
+                    (CardsView::Hidden { .. }, None) => {}
+                    _ => {
+                        return Err(InvalidGameViewUpdateError::VisibilityMismatch);
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The source of time for the game. Abstracted so that the time can be driven manually.
+#[async_trait]
+pub(crate) trait Clock {
+    fn now(&self) -> Instant;
+
+    async fn sleep(&self, duration: Duration);
+}
+
+/// The wall clock provided by tokio.
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct TokioClock;
+
+#[async_trait]
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+}
+
+/// A clock that only moves when told to, to test time limits without waiting.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub(crate) struct ManualClock {
+    start: Instant,
+    elapsed: std::sync::Arc<tokio::sync::watch::Sender<Duration>>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: std::sync::Arc::new(tokio::sync::watch::Sender::new(Duration::ZERO)),
+        }
+    }
+
+    /// Move the time forward, waking up the sleeps that are over.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.send_modify(|elapsed| *elapsed += duration);
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.borrow()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let mut elapsed = self.elapsed.subscribe();
+        let wake_at = *elapsed.borrow() + duration;
+
+        // The sender lives as long as the clock.
+        let _ = elapsed.wait_for(|elapsed| *elapsed >= wake_at).await;
+    }
+}
@@ -1,33 +1,56 @@
+mod attack;
+mod effects;
+mod enhancements;
 mod game_controlflow;
 mod game_recorder;
-mod states;
+mod player_requests;
+mod setup;
+mod specials;
 
 use furuyoni_lib::rules::states::Petals;
 
-use crate::players::Player;
-use derive_more::Neg;
+use crate::game::attack::PendingAttack;
+use crate::players::{ Player, PlayerError };
+use async_recursion::async_recursion;
 use furuyoni_lib::rules::player_actions::{
     BasicAction,
     BasicActionCost,
     HandSelector,
     MainPhaseAction,
     PlayableCardSelector,
+    SpecialSelector,
+};
+use furuyoni_lib::rules::{
+    GameEndReason,
+    GameOutcome,
+    GameResult,
+    GameSummary,
+    ObservePosition,
+    PlayerPos,
 };
-use furuyoni_lib::rules::{ GameResult, ObservePosition, PlayerPos };
 
 use crate::game::game_controlflow::GameControlFlow::{ BreakPhase, Continue };
 use crate::game::game_controlflow::{ GameControlFlow, PhaseBreak };
-use crate::game::game_recorder::{ run_recorder, GameRecorder };
-use crate::game::states::*;
-use crate::game_watcher::{ GameObserver, NotifyFailedError };
-use furuyoni_lib::rules::attack::{ AttackDamage, Damage };
-use furuyoni_lib::rules::cards::{ Card, CardSelector, CardSelectorCase, CardsPosition };
+use crate::game::game_recorder::run_recorder;
+use crate::game_watcher::NotifyFailedError;
+use furuyoni_lib::rules::attack::{ AttackDamage, DamageTarget };
+use furuyoni_lib::rules::cards::{
+    Card,
+    CardPlayData,
+    CardSelector,
+    CardSelectorCase,
+    CardSubType,
+    CardType,
+    CardsPosition,
+};
+use furuyoni_lib::rules::deck_list::{ DeckList, InvalidDeckListError };
 use furuyoni_lib::rules::events::{ GameEvent, UpdateGameState };
+use furuyoni_lib::rules::megami::Megami;
+use rand::{ Rng, RngCore, SeedableRng };
+use rand_chacha::ChaCha8Rng;
 use furuyoni_lib::rules::states::*;
-use states::player_state::PlayerState;
-use std::future::Future;
 use std::marker::{ Send, Sync };
-use std::ops::{ Deref, DerefMut };
+use std::ops::DerefMut;
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::join;
@@ -38,77 +61,197 @@ const GET_ACTION_RETRY_TIMES: usize = 3;
 #[derive(Error, Debug)]
 pub(crate) enum GameError {
     #[error("Failed to communicate with a player.")] PlayerCommunicationFail(PlayerPos),
+    #[error("A player has run out of time.")] TimedOut(PlayerPos),
     #[error("An invalid action has been requested from the player.")] InvalidActionRequested(
         PlayerPos,
     ),
     #[error("{0}")] InvalidGameUpdate(#[from] InvalidGameUpdateError),
     #[error("{0}")] NotifyFailed(#[from] NotifyFailedError),
     #[error("{0}")] EventFilterError(#[from] EventFilterError),
+    #[error("The game recorder has stopped.")] RecorderStopped,
+}
+
+impl GameError {
+    fn from_player_error(player: PlayerPos, error: PlayerError) -> Self {
+        match error {
+            PlayerError::CommunicationFail => GameError::PlayerCommunicationFail(player),
+            PlayerError::TimedOut => GameError::TimedOut(player),
+        }
+    }
+
+    /// Return the player to blame and the reason to end the game with, if the error has been caused
+    /// by a player rather than the game itself.
+    fn forfeit(&self) -> Option<(PlayerPos, GameEndReason)> {
+        match *self {
+            GameError::PlayerCommunicationFail(player) => Some((player, GameEndReason::Disconnect)),
+            GameError::TimedOut(player) => Some((player, GameEndReason::Timeout)),
+            GameError::InvalidActionRequested(player) =>
+                Some((player, GameEndReason::InvalidActionForfeit)),
+            _ => None,
+        }
+    }
+}
+
+/// Why an action can't be played now, to tell the player whose action has been rejected.
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ActionRefusal {
+    #[error("There is no such card in the hand.")] NotInHand,
+    #[error("There is no such unused special card.")] NoSuchSpecial,
+    #[error("The card isn't a reaction.")] NotReaction,
+    #[error(
+        "A Throughout card can only be played as the first action of the main phase."
+    )] ThroughoutAfterFirstAction,
+    #[error("Not enough flare to pay the {0} flare the special card costs.")] FlareCostUnpayable(
+        u32,
+    ),
+    #[error("The attack is out of range.")] OutOfRange,
+    #[error("Not enough vigor to pay for the basic action.")] NoVigor,
+    #[error("{0:?} can't be performed now.")] BasicActionUnperformable(BasicAction),
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum CreateGameError {
+    #[error("Invalid deck list of {0:?}: {1}")] InvalidDeckList(PlayerPos, InvalidDeckListError),
 }
 
 type Players = PlayersData<Box<dyn Player + Send + Sync>>;
 
-#[derive(Debug, Copy, Clone, PartialEq, Neg)]
-pub struct Vigor(i32);
+pub(crate) use game_recorder::{ GameRecorder, RecordedGame };
+pub(crate) use player_requests::handle_player_requests;
 
 pub(crate) struct GameSetup {
     game: Game,
-    event_rx: mpsc::Receiver<GameEvent>,
+    event_rx: mpsc::UnboundedReceiver<GameEvent>,
     recorder: Arc<GameRecorder>,
+    surrender_tx: mpsc::Sender<PlayerPos>,
+    surrender_rx: mpsc::Receiver<PlayerPos>,
 }
 
 struct Game {
     state: GameState,
     players: Players,
-    event_tx: Option<mpsc::Sender<GameEvent>>,
+    /// Every event sent to the players goes to the recorder, which keeps up with the game however
+    /// far behind it is.
+    event_tx: mpsc::UnboundedSender<GameEvent>,
+    /// Attacks in the middle of their resolution. The last one is the innermost attack.
+    pending_attacks: Vec<PendingAttack>,
+    played_cards_this_turn: PlayersData<u32>,
+    summary: GameSummary,
+    /// Every random choice of the game should be made with this, so that a game can be reproduced
+    /// from its seed.
+    rng: ChaCha8Rng,
 }
 pub fn create_game(
     player_1: Box<dyn Player + Sync + Send>,
-    player_2: Box<dyn Player + Sync + Send>
-) -> (GameSetup, Arc<GameRecorder>) {
-    let (tx, rx) = mpsc::channel(20);
+    player_2: Box<dyn Player + Sync + Send>,
+    deck_lists: PlayersData<DeckList>,
+    seed: u64
+) -> Result<(GameSetup, Arc<GameRecorder>), CreateGameError> {
+    for (player, deck_list) in deck_lists.iter() {
+        deck_list.validate().map_err(|e| CreateGameError::InvalidDeckList(player, e))?;
+    }
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let (surrender_tx, surrender_rx) = mpsc::channel(2);
 
-    let state = initialize_game_states();
-    let recorder = Arc::new(GameRecorder::new(state.clone()));
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let state = initialize_game_states(&deck_lists, &mut rng);
+    let recorder = Arc::new(GameRecorder::new(state.clone(), seed, deck_lists));
     let game = Game {
         state,
         players: Players::new(player_1, player_2),
-        event_tx: Some(tx),
+        event_tx: tx,
+        pending_attacks: vec![],
+        played_cards_this_turn: PlayersData::new(0, 0),
+        summary: GameSummary {
+            turns: 0,
+            damage_dealt: PlayersData::new(0, 0),
+            cards_played: PlayersData::new(0, 0),
+        },
+        rng,
     };
 
     let setup = GameSetup {
         game,
         event_rx: rx,
         recorder: recorder.clone(),
+        surrender_tx,
+        surrender_rx,
     };
-    (setup, recorder)
+    Ok((setup, recorder))
 }
 
 impl GameSetup {
-    pub async fn run(mut self) -> Result<GameResult, GameError> {
+    /// Return a sender to let a player surrender at any point of the game.
+    pub fn surrender_sender(&self) -> mpsc::Sender<PlayerPos> {
+        self.surrender_tx.clone()
+    }
+
+    pub async fn run(self) -> Result<GameOutcome, GameError> {
         // broadcast state.
-        let GameSetup { game, event_rx, recorder } = self;
+        let GameSetup { game, event_rx, recorder, surrender_tx, surrender_rx } = self;
+        drop(surrender_tx);
 
         let recorder_task = tokio::spawn(run_recorder(event_rx, recorder));
 
-        let result = game.run().await;
+        let outcome = game.run(surrender_rx).await;
 
-        let recorder_result = recorder_task.await.unwrap();
-        // Todo:
+        // The recorder ends once the game has been dropped, along with its sender. Its error is
+        // reported first, as the game fails whenever its recorder has stopped.
+        recorder_task.await.unwrap()?;
 
-        result
+        outcome
     }
 }
 
 impl Game {
-    pub async fn run(mut self) -> Result<GameResult, GameError> {
+    pub async fn run(
+        mut self,
+        mut surrender_rx: mpsc::Receiver<PlayerPos>
+    ) -> Result<GameOutcome, GameError> {
+        // A player can surrender even while the game is waiting for the other player.
+        let (result, reason) = tokio::select! {
+            end = self.play() =>
+                match end {
+                    Ok(end) => end,
+                    Err(e) =>
+                        match e.forfeit() {
+                            Some((loser, reason)) => (GameResult::Winner(loser.other()), reason),
+                            None => {
+                                return Err(e);
+                            }
+                        }
+                },
+            Some(player) = surrender_rx.recv() =>
+                (GameResult::Winner(player.other()), GameEndReason::Surrender),
+        };
+
+        let outcome = GameOutcome {
+            result,
+            reason,
+            summary: GameSummary {
+                turns: self.state.turn,
+                ..self.summary
+            },
+        };
+        self.notify_game_end(outcome)?;
+
+        Ok(outcome)
+    }
+
+    async fn play(&mut self) -> Result<(GameResult, GameEndReason), GameError> {
         // broadcast state.
         for (p, player) in self.players.iter_mut() {
-            player.initialize_state(&filter_state(ObservePosition::RelativeTo(p), &self.state))?;
+            player
+                .initialize_state(&filter_state(ObservePosition::RelativeTo(p), &self.state))
+                .map_err(|_| GameError::PlayerCommunicationFail(p))?;
         }
 
         self.notify_game_start().await?;
 
+        self.run_game_setup().await?;
+
         // Define phase modifying functions. The phase state should only be modified using these functions.
         fn next_phase(game: &mut Game) -> Result<(), GameError> {
             match game.state.phase {
@@ -130,6 +273,7 @@ impl Game {
             })?;
 
             game.update_state_and_notify(UpdateGameState::SetPhase(Phase::Beginning))?;
+            game.played_cards_this_turn = PlayersData::new(0, 0);
             Ok(())
         }
 
@@ -142,20 +286,18 @@ impl Game {
             };
 
             match phase_result {
-                Continue => next_phase(&mut self)?,
+                Continue => next_phase(self)?,
                 BreakPhase(phase_break) =>
                     match phase_break {
-                        PhaseBreak::EndPhase => next_phase(&mut self)?,
-                        PhaseBreak::EndTurn => next_turn(&mut self)?,
-                        PhaseBreak::EndGame(game_result) => {
-                            break game_result;
+                        PhaseBreak::EndPhase => next_phase(self)?,
+                        PhaseBreak::EndTurn => next_turn(self)?,
+                        PhaseBreak::EndGame(game_result, reason) => {
+                            break (game_result, reason);
                         }
                     }
             }
         };
 
-        self.notify_all(GameEvent::GameEnd { result })?;
-
         Ok(result)
     }
 
@@ -168,21 +310,37 @@ impl Game {
 
     fn notify_all(&mut self, event: GameEvent) -> Result<(), GameError> {
         for (pos, player) in self.players.iter_mut() {
-            player.notify_event(
-                filter_event(&self.state, ObservePosition::RelativeTo(pos), event)?
-            )?;
+            player
+                .notify_event(filter_event(&self.state, ObservePosition::RelativeTo(pos), event)?)
+                .map_err(|_| GameError::PlayerCommunicationFail(pos))?;
         }
-        if let Some(tx) = &self.event_tx {
-            match tx.try_send(event) {
-                Err(_) => {
-                    eprintln!("Failed to send event to the game recorder.");
-                    drop(self.event_tx.take());
-                }
-                Ok(()) => {}
-            }
+        self.record_event(event)
+    }
+
+    /// Notify the end of the game to the players who are still reachable.
+    fn notify_game_end(&mut self, outcome: GameOutcome) -> Result<(), GameError> {
+        let event = GameEvent::GameEnd(outcome);
+
+        for (pos, player) in self.players.iter_mut() {
+            // The loser may have been disconnected.
+            let _ = player.notify_event(
+                filter_event(&self.state, ObservePosition::RelativeTo(pos), event)?
+            );
         }
+        self.record_event(event)
+    }
 
-        Ok(())
+    /// Send the event to the recorder. A game whose events can't all be recorded fails, instead of
+    /// leaving a truncated replay behind.
+    fn record_event(&self, event: GameEvent) -> Result<(), GameError> {
+        self.event_tx.send(event).map_err(|_| GameError::RecorderStopped)
+    }
+
+    /// Tell the player why their response has been refused.
+    fn reject(&mut self, player: PlayerPos, reason: &str) -> Result<(), GameError> {
+        self.players[player]
+            .notify_rejection(reason)
+            .map_err(|_| GameError::PlayerCommunicationFail(player))
     }
 
     async fn notify_game_start(&mut self) -> Result<(), GameError> {
@@ -191,7 +349,7 @@ impl Game {
             p: &mut (impl Player + ?Sized + Send),
             pos: PlayerPos
         ) -> Result<(), GameError> {
-            p.request_game_start(pos).await.map_err(|_| GameError::PlayerCommunicationFail(pos))
+            p.request_game_start(pos).await.map_err(|e| GameError::from_player_error(pos, e))
         }
 
         let PlayersData { p1_data, p2_data } = &mut self.players;
@@ -202,7 +360,8 @@ impl Game {
             notify_start(p2_data.deref_mut(), PlayerPos::P2)
         );
 
-        (a?, b?);
+        a?;
+        b?;
 
         Ok(())
     }
@@ -218,11 +377,17 @@ impl Game {
 
         self.add_to_vigor(turn_player, 1)?;
 
+        self.remove_enhancement_tokens(turn_player).await??;
+        self.return_recurring_specials()?;
+
+        if self.request_reshuffle(turn_player).await? {
+            self.reshuffle_deck(turn_player).await??;
+        }
+
         for _ in 0..2 {
             self.try_draw_card(turn_player).await??;
         }
 
-        // Todo: remove sakura tokens from enhancements, reshuffle deck, ...
         Ok(Continue)
     }
 
@@ -233,12 +398,96 @@ impl Game {
     }
 
     async fn run_end_phase(&mut self) -> Result<GameControlFlow, GameError> {
-        // Todo: move enhancements and in-use cards to the used pile.
+        const HAND_SIZE_LIMIT: usize = 5;
+
+        for player in [PlayerPos::P1, PlayerPos::P2] {
+            self.clean_up_playing(player)?;
+        }
+
+        // Discard down to the hand size limit.
+        let turn_player = self.state.turn_player;
+        let hand_len = self.state.player_states[turn_player].hand.len();
+        if hand_len > HAND_SIZE_LIMIT {
+            self.discard_cards(turn_player, hand_len - HAND_SIZE_LIMIT).await?;
+        }
+
+        Ok(Continue)
+    }
+
+    /// Move the cards left in the playing zone to where they go after being used.
+    fn clean_up_playing(&mut self, player: PlayerPos) -> Result<(), GameError> {
+        while let Some(&card) = self.state.player_states[player].playing.last() {
+            self.transfer_card(
+                CardSelector {
+                    position: CardsPosition::Playing(player),
+                    case: CardSelectorCase::Last,
+                },
+                CardSelector {
+                    position: used_card_position(player, card),
+                    case: CardSelectorCase::PushLast,
+                }
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Ask the player whether to reshuffle the deck. Not asked if there's nothing to reshuffle.
+    async fn request_reshuffle(&mut self, player: PlayerPos) -> Result<bool, GameError> {
+        let player_state = &self.state.player_states[player];
+        if player_state.played_pile.is_empty() && player_state.discard_pile.is_empty() {
+            return Ok(false);
+        }
+
+        let viewable_state = filter_state(ObservePosition::RelativeTo(player), &self.state);
+
+        self.players[player]
+            .reshuffle(&viewable_state).await
+            .map_err(|e| GameError::from_player_error(player, e))
+    }
+
+    fn shuffle_deck(&mut self, player: PlayerPos) -> Result<(), GameError> {
+        let seed = self.rng.next_u64();
+        self.update_state_and_notify(UpdateGameState::ShuffleDeck {
+            player,
+            seed: Some(seed),
+        })
+    }
+
+    /// Put the played pile and the discard pile back to the deck, then shuffle the deck.
+    /// The player takes 1 damage on the life for reshuffling.
+    async fn reshuffle_deck(&mut self, player: PlayerPos) -> Result<GameControlFlow, GameError> {
+        self.notify_all(GameEvent::ReshuffleDeck { player })?;
+
+        for position in [CardsPosition::Played(player), CardsPosition::Discards(player)] {
+            while !self.state.cards(position).is_empty() {
+                self.transfer_card(
+                    CardSelector {
+                        position,
+                        case: CardSelectorCase::Last,
+                    },
+                    CardSelector {
+                        position: CardsPosition::Deck(player),
+                        case: CardSelectorCase::PushLast,
+                    }
+                )?;
+            }
+        }
+        self.shuffle_deck(player)?;
+
+        self.notify_all(GameEvent::TakeDamage {
+            player,
+            target: DamageTarget::Life,
+            amount: 1,
+        })?;
+        self.apply_damage_try_best(PetalsPosition::Life(player), 1).await??;
 
         Ok(Continue)
     }
 
     async fn handle_player_actions(&mut self) -> Result<GameControlFlow, GameError> {
+        // Number of actions done in this main phase. Needed to check the Throughout(전력) cards.
+        let mut actions_done = 0;
+
         // main phase actions loop.
         loop {
             let turn_player = self.state.turn_player;
@@ -250,14 +499,20 @@ impl Game {
                 BasicAction::Recover,
             ]
                 .into_iter()
-                .filter(|action| self.can_play_basic_action(turn_player, *action))
+                .filter(|action| self.can_play_basic_action(turn_player, *action).is_ok())
                 .collect();
 
-            let playable_cards = vec![]; // Todo:
+            let playable_cards = self
+                .card_selectors(turn_player)
+                .into_iter()
+                .filter(|selector| {
+                    self.can_play_card_in_main_phase(turn_player, *selector, actions_done).is_ok()
+                })
+                .collect();
             let available_costs = (0..self.state.player_states[turn_player].hand.len())
                 .map(|i| BasicActionCost::Hand(HandSelector(i)))
-                .chain([BasicActionCost::Vigor].into_iter())
-                .filter(|cost| self.can_pay_basic_action_cost(turn_player, *cost))
+                .chain([BasicActionCost::Vigor])
+                .filter(|cost| self.can_pay_basic_action_cost(turn_player, *cost).is_ok())
                 .collect();
 
             // Todo: some reusable retry function.
@@ -275,10 +530,13 @@ impl Game {
                         &doable_basic_actions,
                         &available_costs
                     ).await
-                    .map_err(|_| GameError::PlayerCommunicationFail(turn_player))?;
+                    .map_err(|e| GameError::from_player_error(turn_player, e))?;
 
-                if self.can_play_main_phase_action(turn_player, action) {
-                    break action;
+                match self.can_play_main_phase_action(turn_player, action, actions_done) {
+                    Ok(()) => {
+                        break action;
+                    }
+                    Err(refusal) => self.reject(turn_player, &refusal.to_string())?,
                 }
                 cnt_try += 1;
                 if cnt_try >= GET_ACTION_RETRY_TIMES {
@@ -286,43 +544,41 @@ impl Game {
                 }
             };
 
-            debug_assert!(self.can_play_main_phase_action(turn_player, action));
+            debug_assert!(self.can_play_main_phase_action(turn_player, action, actions_done).is_ok());
 
-            self.play_main_phase_action(turn_player, action)??;
+            self.play_main_phase_action(turn_player, action).await??;
+            actions_done += 1;
         }
     }
 
     async fn try_draw_card(&mut self, player: PlayerPos) -> Result<GameControlFlow, GameError> {
-        let from = CardSelector {
-            position: CardsPosition::Deck(player),
-            case: CardSelectorCase::Last,
-        };
-        let to = CardSelector {
-            position: CardsPosition::Hand(player),
-            case: CardSelectorCase::PushLast,
-        };
-
-        if self.can_transfer_card(from, to) {
-            self.transfer_card(from, to)?;
+        if !self.state.player_states[player].deck.is_empty() {
+            self.draw_card(player)?;
         } else {
-            self.apply_attack_damage(player, AttackDamage {
-                aura_damage: Some(1),
-                life_damage: Some(1),
-            }).await??;
+            self.apply_attack_damage(
+                player,
+                AttackDamage {
+                    aura_damage: Some(1),
+                    life_damage: Some(1),
+                },
+                None
+            ).await??;
         }
 
         Ok(Continue)
     }
 
-    async fn apply_attack_damage(
-        &mut self,
-        to: PlayerPos,
-        damage: AttackDamage
-    ) -> Result<GameControlFlow, GameError> {
-        // todo: ask user to select where to get the damage.
-
-        self.apply_damage_try_best(PetalsPosition::Life(to), damage.life_damage.unwrap()).await??;
-        Ok(Continue)
+    fn draw_card(&mut self, player: PlayerPos) -> Result<(), GameError> {
+        self.transfer_card(
+            CardSelector {
+                position: CardsPosition::Deck(player),
+                case: CardSelectorCase::Last,
+            },
+            CardSelector {
+                position: CardsPosition::Hand(player),
+                case: CardSelectorCase::PushLast,
+            }
+        )
     }
 
     async fn apply_damage_try_best(
@@ -356,7 +612,7 @@ impl Game {
         Ok(())
     }
 
-    fn play_main_phase_action(
+    async fn play_main_phase_action(
         &mut self,
         player: PlayerPos,
         action: MainPhaseAction
@@ -368,10 +624,85 @@ impl Game {
                 self.play_basic_action(player, action)?;
                 Ok(Continue)
             }
-            MainPhaseAction::PlayCard(_) => {
-                todo!();
+            MainPhaseAction::PlayCard(selector) => {
+                let card = self.playable_card(player, selector).expect("Should have been checked.");
+
+                self.play_card(player, selector).await??;
+
+                // Playing a Throughout(전력) card ends the main phase.
+                if let CardSubType::Throughout = card.data().card_sub_type {
+                    return Ok(BreakPhase(PhaseBreak::EndPhase));
+                }
+                Ok(Continue)
+            }
+        }
+    }
+
+    /// Play a card: move it to the playing zone, resolve it, then put it to the played pile.
+    /// Enhancement cards are put to the enhancements zone instead, while being resolved.
+    /// Special cards are paid with flare and become used instead of going to the played pile.
+    #[async_recursion]
+    async fn play_card(
+        &mut self,
+        player: PlayerPos,
+        selector: PlayableCardSelector
+    ) -> Result<GameControlFlow, GameError> {
+        let card = self.playable_card(player, selector).expect("Should have been checked.");
+
+        if let CardType::Special { flare_cost, .. } = card.data().card_type {
+            self.pay_flare(player, flare_cost)??;
+        }
+
+        self.transfer_card(
+            playable_card_position(player, selector),
+            CardSelector {
+                position: CardsPosition::Playing(player),
+                case: CardSelectorCase::PushLast,
+            }
+        )?;
+        self.notify_all(GameEvent::PlayCard { player, card })?;
+        self.played_cards_this_turn[player] += 1;
+        self.summary.cards_played[player] += 1;
+
+        let resolve_result = self.resolve_card_play_data(player, card).await?;
+
+        // The card leaves the playing zone even if the game or the phase is over.
+        if !matches!(card.data().play_data, CardPlayData::EnhancementCard { .. }) {
+            self.transfer_card(
+                CardSelector {
+                    position: CardsPosition::Playing(player),
+                    case: CardSelectorCase::Last,
+                },
+                CardSelector {
+                    position: used_card_position(player, card),
+                    case: CardSelectorCase::PushLast,
+                }
+            )?;
+        }
+
+        self.return_recurring_specials()?;
+
+        Ok(resolve_result)
+    }
+
+    async fn resolve_card_play_data(
+        &mut self,
+        player: PlayerPos,
+        card: Card
+    ) -> Result<GameControlFlow, GameError> {
+        match &card.data().play_data {
+            CardPlayData::AttackCard { attack } => {
+                self.attack(player, attack).await??;
+            }
+            CardPlayData::ActionCard { effects } => {
+                self.run_effects(player, effects).await??;
+            }
+            CardPlayData::EnhancementCard { charge, .. } => {
+                self.enhance(player, *charge).await??;
             }
         }
+
+        Ok(Continue)
     }
 
     fn pay_basic_action_cost(
@@ -451,24 +782,30 @@ impl Game {
 
         let has_lost = |p: PlayerPos| -> bool { state.player_states[p].life.count == 0 };
 
-        let res = match (has_lost(PlayerPos::P1), has_lost(PlayerPos::P2)) {
-            (true, true) => BreakPhase(PhaseBreak::EndGame(GameResult::Draw)),
-            (true, false) => BreakPhase(PhaseBreak::EndGame(GameResult::Winner(PlayerPos::P2))),
-            (false, true) => BreakPhase(PhaseBreak::EndGame(GameResult::Winner(PlayerPos::P1))),
-            (false, false) => Continue,
+        let result = match (has_lost(PlayerPos::P1), has_lost(PlayerPos::P2)) {
+            (true, true) => GameResult::Draw,
+            (true, false) => GameResult::Winner(PlayerPos::P2),
+            (false, true) => GameResult::Winner(PlayerPos::P1),
+            (false, false) => {
+                return Ok(Continue);
+            }
         };
 
-        Ok(res)
+        Ok(BreakPhase(PhaseBreak::EndGame(result, GameEndReason::LifeReachedZero)))
     }
 
     fn master_interval(&self) -> i32 {
         2
     }
 
-    fn can_play_basic_action(&self, player: PlayerPos, action: BasicAction) -> bool {
-        let mut can_transfer_petals = |from, to| self.can_transfer_petals(from, to, 1);
+    fn can_play_basic_action(
+        &self,
+        player: PlayerPos,
+        action: BasicAction
+    ) -> Result<(), ActionRefusal> {
+        let can_transfer_petals = |from, to| self.can_transfer_petals(from, to, 1);
 
-        match action {
+        let can_play = match action {
             BasicAction::MoveForward => {
                 can_transfer_petals(PetalsPosition::Distance, PetalsPosition::Aura(player)) &&
                     (self.state.distance.count as i32) > self.master_interval()
@@ -482,13 +819,27 @@ impl Game {
             BasicAction::Focus => {
                 can_transfer_petals(PetalsPosition::Aura(player), PetalsPosition::Flare(player))
             }
-        }
+        };
+
+        if can_play { Ok(()) } else { Err(ActionRefusal::BasicActionUnperformable(action)) }
     }
 
-    fn can_pay_basic_action_cost(&self, player: PlayerPos, cost: BasicActionCost) -> bool {
+    fn can_pay_basic_action_cost(
+        &self,
+        player: PlayerPos,
+        cost: BasicActionCost
+    ) -> Result<(), ActionRefusal> {
         match cost {
-            BasicActionCost::Hand(selector) => self.can_discard_card_from_hand(player, selector),
-            BasicActionCost::Vigor => self.can_add_to_vigor(player, -1),
+            BasicActionCost::Hand(selector) => {
+                if self.can_discard_card_from_hand(player, selector) {
+                    Ok(())
+                } else {
+                    Err(ActionRefusal::NotInHand)
+                }
+            }
+            BasicActionCost::Vigor => {
+                if self.can_add_to_vigor(player, -1) { Ok(()) } else { Err(ActionRefusal::NoVigor) }
+            }
         }
     }
 
@@ -496,14 +847,109 @@ impl Game {
         self.state.player_states[player].vigor.0 + diff >= 0
     }
 
-    fn can_play_main_phase_action(&self, player: PlayerPos, action: MainPhaseAction) -> bool {
+    fn can_play_main_phase_action(
+        &self,
+        player: PlayerPos,
+        action: MainPhaseAction,
+        actions_done: usize
+    ) -> Result<(), ActionRefusal> {
         match action {
-            MainPhaseAction::EndMainPhase => true,
+            MainPhaseAction::EndMainPhase => Ok(()),
             MainPhaseAction::PlayBasicAction { action, cost } => {
-                self.can_pay_basic_action_cost(player, cost) &&
-                    self.can_play_basic_action(player, action)
+                self.can_pay_basic_action_cost(player, cost)?;
+                self.can_play_basic_action(player, action)
             }
-            MainPhaseAction::PlayCard(_) => false,
+            MainPhaseAction::PlayCard(selector) =>
+                self.can_play_card_in_main_phase(player, selector, actions_done),
+        }
+    }
+
+    fn playable_card(&self, player: PlayerPos, selector: PlayableCardSelector) -> Option<Card> {
+        match selector {
+            PlayableCardSelector::Hand(HandSelector(index)) =>
+                self.state.player_states[player].hand.get(index).copied(),
+            PlayableCardSelector::Special(SpecialSelector(index)) =>
+                self.state.player_states[player].specials.get(index).copied(),
+        }
+    }
+
+    /// The card to play, or why it doesn't exist.
+    fn card_to_play(
+        &self,
+        player: PlayerPos,
+        selector: PlayableCardSelector
+    ) -> Result<Card, ActionRefusal> {
+        self.playable_card(player, selector).ok_or(match selector {
+            PlayableCardSelector::Hand(_) => ActionRefusal::NotInHand,
+            PlayableCardSelector::Special(_) => ActionRefusal::NoSuchSpecial,
+        })
+    }
+
+    /// Return the selectors of the cards the player may play: the hand and the unused specials.
+    fn card_selectors(&self, player: PlayerPos) -> Vec<PlayableCardSelector> {
+        let player_state = &self.state.player_states[player];
+
+        (0..player_state.hand.len())
+            .map(|i| PlayableCardSelector::Hand(HandSelector(i)))
+            .chain(
+                (0..player_state.specials.len()).map(|i| {
+                    PlayableCardSelector::Special(SpecialSelector(i))
+                })
+            )
+            .collect()
+    }
+
+    fn can_play_card_in_main_phase(
+        &self,
+        player: PlayerPos,
+        selector: PlayableCardSelector,
+        actions_done: usize
+    ) -> Result<(), ActionRefusal> {
+        let card = self.card_to_play(player, selector)?;
+
+        // Throughout(전력) cards can only be played as the first action of the main phase.
+        if let CardSubType::Throughout = card.data().card_sub_type && actions_done > 0 {
+            return Err(ActionRefusal::ThroughoutAfterFirstAction);
+        }
+
+        self.can_play_card(player, selector)
+    }
+
+    fn can_play_reaction(
+        &self,
+        player: PlayerPos,
+        selector: PlayableCardSelector
+    ) -> Result<(), ActionRefusal> {
+        let card = self.card_to_play(player, selector)?;
+
+        if let CardSubType::Reaction = card.data().card_sub_type {
+            self.can_play_card(player, selector)
+        } else {
+            Err(ActionRefusal::NotReaction)
+        }
+    }
+
+    fn can_play_card(
+        &self,
+        player: PlayerPos,
+        selector: PlayableCardSelector
+    ) -> Result<(), ActionRefusal> {
+        let data = self.card_to_play(player, selector)?.data();
+
+        match data.card_type {
+            CardType::Normal => {}
+            CardType::Special { flare_cost, .. } => {
+                if self.state.player_states[player].flare.count < flare_cost {
+                    return Err(ActionRefusal::FlareCostUnpayable(flare_cost));
+                }
+            }
+        }
+
+        match &data.play_data {
+            CardPlayData::AttackCard { attack } => {
+                if self.is_in_range(attack.range) { Ok(()) } else { Err(ActionRefusal::OutOfRange) }
+            }
+            CardPlayData::ActionCard { .. } | CardPlayData::EnhancementCard { .. } => Ok(()),
         }
     }
 
@@ -548,80 +994,37 @@ impl Game {
                 position: CardsPosition::Discards(player),
                 case: CardSelectorCase::PushLast,
             }
-        );
-        true
+        )
     }
 }
 
-#[derive(Debug, Error)]
-#[error("Failed to filter an event: {0:?}")]
-struct EventFilterError(GameEvent);
-fn filter_event(
-    state: &GameState,
-    position: ObservePosition,
-    event: GameEvent
-) -> Result<GameEvent, EventFilterError> /* Todo: to Result<Option<GameEvent>, ?> */ {
-    let (can_view_p1, can_view_p2, can_view_master) = {
-        match position {
-            ObservePosition::RelativeTo(p) => (p == PlayerPos::P1, p == PlayerPos::P2, false),
-            ObservePosition::MasterView => (true, true, true),
-            ObservePosition::ByStander => (false, false, false),
-        }
-    };
-    let can_view_p = PlayersData::new(can_view_p1, can_view_p2);
-
-    let e = match event {
-        e @ GameEvent::StateUpdated(update) =>
-            GameEvent::StateUpdated(match update {
-                | u @ UpdateGameState::SetTurn { .. }
-                | u @ UpdateGameState::SetPhase(_)
-                | u @ UpdateGameState::TransferPetals { .. }
-                | u @ UpdateGameState::AddToVigor { .. } => u,
-                u @ UpdateGameState::TransferCard { from, to } => {
-                    let is_from_open = is_open(can_view_master, &can_view_p, &from.position);
-                    let is_to_open = is_open(can_view_master, &can_view_p, &to.position);
-
-                    if is_from_open {
-                        u
-                    } else {
-                        UpdateGameState::TransferCardFromHidden {
-                            from: from.position,
-                            to,
-                            card: if is_to_open {
-                                Some(state.select_card(from).ok_or(EventFilterError(e))?)
-                            } else {
-                                None
-                            },
-                        }
-                    }
-                }
-
-                UpdateGameState::TransferCardFromHidden { .. } => {
-                    panic!();
-                }
-            }),
-        e => e,
-    };
-
-    Ok(e)
+fn playable_card_position(player: PlayerPos, selector: PlayableCardSelector) -> CardSelector {
+    match selector {
+        PlayableCardSelector::Hand(HandSelector(index)) =>
+            CardSelector {
+                position: CardsPosition::Hand(player),
+                case: CardSelectorCase::Index { index },
+            },
+        PlayableCardSelector::Special(SpecialSelector(index)) =>
+            CardSelector {
+                position: CardsPosition::Specials(player),
+                case: CardSelectorCase::Index { index },
+            },
+    }
 }
 
-fn is_open(
-    can_view_master: bool,
-    can_view_p: &PlayersData<bool>,
-    position: &CardsPosition
-) -> bool {
-    match position {
-        CardsPosition::Deck(_) => can_view_master,
-        CardsPosition::Discards(p) | CardsPosition::Hand(p) => can_view_p[p],
-        CardsPosition::Playing(_) | CardsPosition::Enhancements(_) | CardsPosition::Played(_) =>
-            true,
+/// Return where the card goes after being used: the used specials for special cards, or the played
+/// pile for the others.
+fn used_card_position(player: PlayerPos, card: Card) -> CardsPosition {
+    match card.data().card_type {
+        CardType::Normal => CardsPosition::Played(player),
+        CardType::Special { .. } => CardsPosition::UsedSpecials(player),
     }
 }
 
-fn initialize_game_states() -> GameState {
+fn initialize_game_states(deck_lists: &PlayersData<DeckList>, rng: &mut ChaCha8Rng) -> GameState {
     // Select starting player.
-    let start_player = if rand::random::<bool>() { PlayerPos::P1 } else { PlayerPos::P2 };
+    let start_player = if rng.gen_bool(0.5) { PlayerPos::P1 } else { PlayerPos::P2 };
 
     GameState::new(
         1,
@@ -629,53 +1032,26 @@ fn initialize_game_states() -> GameState {
         Phase::Beginning,
         Petals::new(10, Some(10)),
         Petals::new(0, None),
-        default_player_states()
+        PlayerStates::new(
+            PlayerState::from_deck_list(&deck_lists[PlayerPos::P1]),
+            PlayerState::from_deck_list(&deck_lists[PlayerPos::P2])
+        )
     )
 }
 
-/// Return default player states. Only used for debugging.
-fn default_player_states() -> PlayerStates {
-    let p1_state = PlayerState {
-        deck: Vec::from([
-            Card::Slash,
+/// Return a default deck list. Only used for debugging.
+pub(crate) fn default_deck_list() -> DeckList {
+    DeckList {
+        megamis: [Megami::Yurina, Megami::Himika],
+        normals: vec![
             Card::Slash,
-            Card::Slash,
-            Card::Slash,
-            Card::Slash,
-            Card::Slash,
-            Card::Slash,
-        ]),
-        ..Default::default()
-    };
-
-    let p2_state = PlayerState {
-        deck: Vec::from([
-            Card::Slash,
-            Card::Slash,
-            Card::Slash,
-            Card::Slash,
-            Card::Slash,
-            Card::Slash,
-            Card::Slash,
-        ]),
-        ..Default::default()
-    };
-
-    PlayerStates::new(p1_state, p2_state)
-}
-
-fn filter_state(viewed_from: ObservePosition, state: &GameState) -> StateView {
-    let player_states = &state.player_states;
-
-    StateView {
-        turn_player: state.turn_player,
-        phase: state.phase,
-        turn: state.turn,
-        distance: state.distance.clone(),
-        dust: state.dust.clone(),
-        player_states: PlayerStateViews::new(
-            player_states[PlayerPos::P1].as_viewed_from(PlayerPos::P1, viewed_from),
-            player_states[PlayerPos::P2].as_viewed_from(PlayerPos::P2, viewed_from)
-        ),
+            Card::Brandish,
+            Card::Bash,
+            Card::Iai,
+            Card::Footwork,
+            Card::Pressure,
+            Card::Spirit
+        ],
+        specials: vec![Card::MoonShadowFall, Card::WaveStorm, Card::FloatingBoat],
     }
 }
@@ -0,0 +1,228 @@
+use crate::game::game_controlflow::GameControlFlow;
+use crate::game::game_controlflow::GameControlFlow::Continue;
+use crate::game::{ Game, GameError, GET_ACTION_RETRY_TIMES };
+use furuyoni_lib::rules::attack::{ Attack, AttackDamage, DamageModifier, DamageTarget };
+use furuyoni_lib::rules::events::{ AttackEvent, GameEvent };
+use furuyoni_lib::rules::player_actions::ReactionAction;
+use furuyoni_lib::rules::states::{ filter_state, PetalsPosition };
+use furuyoni_lib::rules::{ ObservePosition, PlayerPos };
+
+/// An attack in the middle of its resolution. Reactions to the attack can cancel or modify it.
+pub(super) struct PendingAttack {
+    pub attacker: PlayerPos,
+    pub cancelled: bool,
+    pub damage_modifiers: Vec<DamageModifier>,
+}
+
+impl Game {
+    /// Resolve an attack of the attacker to the other player.
+    pub(super) async fn attack(
+        &mut self,
+        attacker: PlayerPos,
+        attack: &'static Attack
+    ) -> Result<GameControlFlow, GameError> {
+        self.notify_all(
+            GameEvent::Attack(AttackEvent::Declared {
+                attacker,
+                base_damage: attack.base_damage,
+            })
+        )?;
+
+        // Attacks made while resolving another attack (e.g. reaction attacks) can't be reacted.
+        let can_be_reacted = self.pending_attacks.is_empty();
+
+        self.pending_attacks.push(PendingAttack {
+            attacker,
+            cancelled: false,
+            damage_modifiers: vec![],
+        });
+        let result = self.resolve_attack(attacker, attack, can_be_reacted).await;
+        self.pending_attacks.pop();
+
+        result
+    }
+
+    async fn resolve_attack(
+        &mut self,
+        attacker: PlayerPos,
+        attack: &'static Attack,
+        can_be_reacted: bool
+    ) -> Result<GameControlFlow, GameError> {
+        if can_be_reacted {
+            self.open_reaction_window(attacker, attack).await??;
+        }
+
+        let pending = self.pending_attacks.last().expect("The attack should be pending.");
+        if pending.cancelled {
+            self.notify_all(GameEvent::Attack(AttackEvent::Cancelled))?;
+            return Ok(Continue);
+        }
+
+        // The distance could have been changed by the reaction.
+        if !self.is_in_range(attack.range) {
+            self.notify_all(GameEvent::Attack(AttackEvent::OutOfRange))?;
+            return Ok(Continue);
+        }
+
+        let damage = self.modified_damage(attacker, attack);
+        self.notify_all(GameEvent::Attack(AttackEvent::DamageDetermined { damage }))?;
+
+        self.apply_attack_damage(attacker.other(), damage, Some(attacker)).await??;
+
+        self.notify_all(GameEvent::Attack(AttackEvent::AfterAttack))?;
+        self.run_effects(attacker, attack.after_attack).await??;
+
+        self.notify_all(GameEvent::Attack(AttackEvent::Resolved))?;
+        Ok(Continue)
+    }
+
+    /// Let the defender play a reaction card before the attack resolves.
+    async fn open_reaction_window(
+        &mut self,
+        attacker: PlayerPos,
+        attack: &'static Attack
+    ) -> Result<GameControlFlow, GameError> {
+        let defender = attacker.other();
+
+        let playable_reactions: Vec<_> = self
+            .card_selectors(defender)
+            .into_iter()
+            .filter(|selector| self.can_play_reaction(defender, *selector).is_ok())
+            .collect();
+
+        if playable_reactions.is_empty() {
+            return Ok(Continue);
+        }
+
+        let attack_damage = self.modified_damage(attacker, attack);
+
+        let mut cnt_try = 0;
+        let action = loop {
+            let viewable_state = filter_state(ObservePosition::RelativeTo(defender), &self.state);
+
+            let action = self.players[defender]
+                .reaction(&viewable_state, attack_damage, &playable_reactions).await
+                .map_err(|e| GameError::from_player_error(defender, e))?;
+
+            let result = match action {
+                ReactionAction::Pass => Ok(()),
+                ReactionAction::PlayReaction(selector) => self.can_play_reaction(defender, selector),
+            };
+            match result {
+                Ok(()) => {
+                    break action;
+                }
+                Err(refusal) => self.reject(defender, &refusal.to_string())?,
+            }
+            cnt_try += 1;
+            if cnt_try >= GET_ACTION_RETRY_TIMES {
+                return Err(GameError::InvalidActionRequested(defender));
+            }
+        };
+
+        match action {
+            ReactionAction::Pass => Ok(Continue),
+            ReactionAction::PlayReaction(selector) => self.play_card(defender, selector).await,
+        }
+    }
+
+    pub(super) fn is_in_range(&self, range: &[i32]) -> bool {
+        range.contains(&(self.state.distance.count as i32))
+    }
+
+    /// Return the attack's damage after applying its own damage modifiers, then the modifiers
+    /// added to the pending attack.
+    fn modified_damage(&self, attacker: PlayerPos, attack: &Attack) -> AttackDamage {
+        let damage = attack.damage_modifiers
+            .iter()
+            .filter(|(condition, _)| self.check_condition(attacker, *condition))
+            .fold(attack.base_damage, |damage, (_, modifier)| modifier.apply(damage));
+
+        match self.pending_attacks.last() {
+            Some(pending) =>
+                pending.damage_modifiers
+                    .iter()
+                    .fold(damage, |damage, modifier| modifier.apply(damage)),
+            None => damage,
+        }
+    }
+
+    /// Let the player take the damage. The damage is counted as dealt by the dealer, if the dealer
+    /// is the player's opponent.
+    pub(super) async fn apply_attack_damage(
+        &mut self,
+        to: PlayerPos,
+        damage: AttackDamage,
+        dealer: Option<PlayerPos>
+    ) -> Result<GameControlFlow, GameError> {
+        let targets = self.available_damage_targets(to, damage);
+
+        let target = match targets.as_slice() {
+            [] => {
+                return Ok(Continue);
+            }
+            [target] => *target,
+            _ => self.request_damage_target(to, damage, &targets).await?,
+        };
+
+        let (petals_pos, amount) = match target {
+            DamageTarget::Aura => (PetalsPosition::Aura(to), damage.aura_damage),
+            DamageTarget::Life => (PetalsPosition::Life(to), damage.life_damage),
+        };
+        let amount = amount.expect("Only targets with damages should be available.");
+
+        self.notify_all(GameEvent::TakeDamage { player: to, target, amount })?;
+
+        if let Some(dealer) = dealer && dealer != to {
+            let taken = amount.min(self.state.petals(petals_pos).count);
+            self.summary.damage_dealt[dealer] += taken;
+        }
+        self.apply_damage_try_best(petals_pos, amount).await??;
+        Ok(Continue)
+    }
+
+    async fn request_damage_target(
+        &mut self,
+        defender: PlayerPos,
+        damage: AttackDamage,
+        targets: &Vec<DamageTarget>
+    ) -> Result<DamageTarget, GameError> {
+        let mut cnt_try = 0;
+        loop {
+            let viewable_state = filter_state(ObservePosition::RelativeTo(defender), &self.state);
+
+            let target = self.players[defender]
+                .damage_target(&viewable_state, damage, targets).await
+                .map_err(|e| GameError::from_player_error(defender, e))?;
+
+            if targets.contains(&target) {
+                return Ok(target);
+            }
+            self.reject(defender, "The damage can't be taken on the target.")?;
+            cnt_try += 1;
+            if cnt_try >= GET_ACTION_RETRY_TIMES {
+                return Err(GameError::InvalidActionRequested(defender));
+            }
+        }
+    }
+
+    /// Return where the defender can take the damage.
+    /// A player can't take the damage on the aura if the aura can't absorb all of it, unless the
+    /// life damage is "-".
+    pub(super) fn available_damage_targets(
+        &self,
+        defender: PlayerPos,
+        damage: AttackDamage
+    ) -> Vec<DamageTarget> {
+        let aura = self.state.player_states[defender].aura.count;
+
+        match (damage.aura_damage, damage.life_damage) {
+            (None, None) => vec![],
+            (Some(_), None) => vec![DamageTarget::Aura],
+            (None, Some(_)) => vec![DamageTarget::Life],
+            (Some(aura_damage), Some(_)) if aura_damage <= aura =>
+                vec![DamageTarget::Aura, DamageTarget::Life],
+            (Some(_), Some(_)) => vec![DamageTarget::Life],
+        }
+    }
+}
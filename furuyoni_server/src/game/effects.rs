@@ -0,0 +1,190 @@
+use crate::game::game_controlflow::GameControlFlow;
+use crate::game::game_controlflow::GameControlFlow::Continue;
+use crate::game::{ Game, GameError, GET_ACTION_RETRY_TIMES };
+use async_recursion::async_recursion;
+use furuyoni_lib::rules::condition::Condition;
+use furuyoni_lib::rules::effects::{ Effect, EffectOption };
+use furuyoni_lib::rules::player_actions::HandSelector;
+use furuyoni_lib::rules::states::{ filter_state, PetalsPosition };
+use furuyoni_lib::rules::{ ObservePosition, PlayerPos };
+
+impl Game {
+    #[async_recursion]
+    pub(super) async fn run_effects(
+        &mut self,
+        user: PlayerPos,
+        effects: &'static [Effect]
+    ) -> Result<GameControlFlow, GameError> {
+        for effect in effects {
+            self.run_effect(user, *effect).await??;
+        }
+        Ok(Continue)
+    }
+
+    async fn run_effect(
+        &mut self,
+        user: PlayerPos,
+        effect: Effect
+    ) -> Result<GameControlFlow, GameError> {
+        match effect {
+            Effect::TransferPetals { from, to, amount } => {
+                self.transfer_petals_try_best(
+                    from.into_absolute(user),
+                    to.into_absolute(user),
+                    amount
+                )??;
+            }
+            Effect::Draw { player, amount } => {
+                for _ in 0..amount {
+                    self.try_draw_card(player.into_absolute(user)).await??;
+                }
+            }
+            Effect::Discard { player, amount } => {
+                self.discard_cards(player.into_absolute(user), amount as usize).await?;
+            }
+            Effect::ChangeDistance(diff) => {
+                let (from, to) = if diff >= 0 {
+                    (PetalsPosition::Dust, PetalsPosition::Distance)
+                } else {
+                    (PetalsPosition::Distance, PetalsPosition::Dust)
+                };
+                self.transfer_petals_try_best(from, to, diff.unsigned_abs())??;
+            }
+            Effect::AddToVigor { player, diff } => {
+                let player = player.into_absolute(user);
+                let vigor = self.state.player_states[player].vigor.0;
+                self.add_to_vigor(player, std::cmp::max(diff, -vigor))?;
+            }
+            Effect::Damage { player, damage } => {
+                self.apply_attack_damage(player.into_absolute(user), damage, Some(user)).await??;
+            }
+            Effect::Choice(options) => {
+                let option = self.request_effect_choice(user, options).await?;
+                self.run_effects(user, option.effects).await??;
+            }
+            Effect::If { condition, then, otherwise } => {
+                let effects = if self.check_condition(user, condition) { then } else { otherwise };
+                self.run_effects(user, effects).await??;
+            }
+            Effect::CancelReactedAttack => {
+                if let Some(reacted) = self.pending_attacks.first_mut() {
+                    reacted.cancelled = true;
+                }
+            }
+            Effect::ModifyReactedAttack(modifier) => {
+                if let Some(reacted) = self.pending_attacks.first_mut() {
+                    reacted.damage_modifiers.push(modifier);
+                }
+            }
+        }
+
+        Ok(Continue)
+    }
+
+    pub(super) fn check_condition(&self, user: PlayerPos, condition: Condition) -> bool {
+        let state = &self.state;
+
+        match condition {
+            Condition::Not(condition) => !self.check_condition(user, *condition),
+            Condition::All(conditions) =>
+                conditions.iter().all(|condition| self.check_condition(user, *condition)),
+            Condition::Any(conditions) =>
+                conditions.iter().any(|condition| self.check_condition(user, *condition)),
+            Condition::DistanceAtMost(distance) => state.distance.count <= distance,
+            Condition::DistanceAtLeast(distance) => state.distance.count >= distance,
+            Condition::PetalsAtMost { position, count } =>
+                state.petals(position.into_absolute(user)).count <= count,
+            Condition::PetalsAtLeast { position, count } =>
+                state.petals(position.into_absolute(user)).count >= count,
+            Condition::VigorAtLeast { player, vigor } =>
+                state.player_states[player.into_absolute(user)].vigor.0 >= vigor,
+            Condition::HandAtLeast { player, count } =>
+                (state.player_states[player.into_absolute(user)].hand.len() as u32) >= count,
+            Condition::PlayedCardsThisTurn { player, at_least } =>
+                self.played_cards_this_turn[player.into_absolute(user)] >= at_least,
+            Condition::IsReacting =>
+                matches!(self.pending_attacks.first(), Some(reacted) if reacted.attacker == user.other()),
+        }
+    }
+
+    /// Move as many petals as possible, up to the given amount.
+    pub(super) fn transfer_petals_try_best(
+        &mut self,
+        from: PetalsPosition,
+        to: PetalsPosition,
+        amount: u32
+    ) -> Result<GameControlFlow, GameError> {
+        let to_petals = self.state.petals(to);
+        let space = to_petals.max.map_or(u32::MAX, |max| max.saturating_sub(to_petals.count));
+        let amount = amount.min(self.state.petals(from).count).min(space);
+
+        if amount == 0 {
+            return Ok(Continue);
+        }
+        self.transfer_petals(from, to, amount)
+    }
+
+    /// Let the player discard(伏せ) the given number of cards of their choice from the hand.
+    pub(super) async fn discard_cards(&mut self, player: PlayerPos, amount: usize) -> Result<(), GameError> {
+        let hand_len = self.state.player_states[player].hand.len();
+        let amount = std::cmp::min(amount, hand_len);
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let mut cnt_try = 0;
+        let mut selected = loop {
+            let viewable_state = filter_state(ObservePosition::RelativeTo(player), &self.state);
+
+            let mut selected = self.players[player]
+                .cards_to_discard(&viewable_state, amount).await
+                .map_err(|e| GameError::from_player_error(player, e))?;
+
+            selected.sort_by_key(|HandSelector(i)| *i);
+            selected.dedup();
+            if selected.len() == amount && selected.iter().all(|HandSelector(i)| *i < hand_len) {
+                break selected;
+            }
+            self.reject(player, &format!("Select {amount} different card(s) in the hand."))?;
+            cnt_try += 1;
+            if cnt_try >= GET_ACTION_RETRY_TIMES {
+                return Err(GameError::InvalidActionRequested(player));
+            }
+        };
+
+        // Discard from the back so that the remaining selectors stay valid.
+        while let Some(selector) = selected.pop() {
+            self.discard_card_from_hand(player, selector)?;
+        }
+        Ok(())
+    }
+
+    async fn request_effect_choice(
+        &mut self,
+        user: PlayerPos,
+        options: &'static [EffectOption]
+    ) -> Result<&'static EffectOption, GameError> {
+        let descriptions: Vec<_> = options
+            .iter()
+            .map(|option| option.description.to_string())
+            .collect();
+
+        let mut cnt_try = 0;
+        loop {
+            let viewable_state = filter_state(ObservePosition::RelativeTo(user), &self.state);
+
+            let index = self.players[user]
+                .effect_choice(&viewable_state, &descriptions).await
+                .map_err(|e| GameError::from_player_error(user, e))?;
+
+            if let Some(option) = options.get(index) {
+                return Ok(option);
+            }
+            self.reject(user, "There is no such option.")?;
+            cnt_try += 1;
+            if cnt_try >= GET_ACTION_RETRY_TIMES {
+                return Err(GameError::InvalidActionRequested(user));
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::game::game_controlflow::GameControlFlow;
+use crate::game::game_controlflow::GameControlFlow::Continue;
+use crate::game::{ used_card_position, Game, GameError };
+use furuyoni_lib::rules::cards::{ CardPlayData, CardSelector, CardSelectorCase, CardsPosition };
+use furuyoni_lib::rules::events::GameEvent;
+use furuyoni_lib::rules::states::PetalsPosition;
+use furuyoni_lib::rules::PlayerPos;
+
+impl Game {
+    /// Put the enhancement card being played to the enhancements zone, then charge(납) sakura
+    /// tokens on it from the aura. The lack is charged from the flare.
+    pub(super) async fn enhance(
+        &mut self,
+        player: PlayerPos,
+        charge: u32
+    ) -> Result<GameControlFlow, GameError> {
+        self.transfer_card(
+            CardSelector {
+                position: CardsPosition::Playing(player),
+                case: CardSelectorCase::Last,
+            },
+            CardSelector {
+                position: CardsPosition::Enhancements(player),
+                case: CardSelectorCase::PushLast,
+            }
+        )?;
+
+        let index = self.state.player_states[player].enhancements.len() - 1;
+        let tokens = PetalsPosition::Enhancement { player, index };
+
+        self.transfer_petals_try_best(PetalsPosition::Aura(player), tokens, charge)??;
+        let lack = charge - self.state.petals(tokens).count;
+        self.transfer_petals_try_best(PetalsPosition::Flare(player), tokens, lack)??;
+
+        // An enhancement without any token is disenhanced right away.
+        self.disenhance_empty_enhancements(player).await
+    }
+
+    /// Remove a sakura token from each of the player's enhancement cards, then disenhance the ones
+    /// that ran out of tokens.
+    pub(super) async fn remove_enhancement_tokens(
+        &mut self,
+        player: PlayerPos
+    ) -> Result<GameControlFlow, GameError> {
+        for index in 0..self.state.player_states[player].enhancements.len() {
+            self.transfer_petals_try_best(
+                PetalsPosition::Enhancement { player, index },
+                PetalsPosition::Dust,
+                1
+            )??;
+        }
+
+        self.disenhance_empty_enhancements(player).await
+    }
+
+    /// Move the enhancement cards without tokens to the used zone and resolve their
+    /// on-disenhance(파기시) effects, one card at a time.
+    async fn disenhance_empty_enhancements(
+        &mut self,
+        player: PlayerPos
+    ) -> Result<GameControlFlow, GameError> {
+        while
+            let Some(index) = self.state.player_states[player].enhancement_tokens
+                .iter()
+                .position(|tokens| tokens.count == 0)
+        {
+            let card = self.state.player_states[player].enhancements[index];
+            self.notify_all(GameEvent::Disenhance { player, card })?;
+
+            self.transfer_card(
+                CardSelector {
+                    position: CardsPosition::Enhancements(player),
+                    case: CardSelectorCase::Index { index },
+                },
+                CardSelector {
+                    position: used_card_position(player, card),
+                    case: CardSelectorCase::PushLast,
+                }
+            )?;
+
+            if let CardPlayData::EnhancementCard { on_disenhance, .. } = &card.data().play_data {
+                self.run_effects(player, on_disenhance).await??;
+            }
+        }
+
+        Ok(Continue)
+    }
+}
@@ -1,4 +1,4 @@
-use furuyoni_lib::rules::GameResult;
+use furuyoni_lib::rules::{GameEndReason, GameResult};
 use std::ops::{ControlFlow, FromResidual, Try};
 
 pub enum GameControlFlow {
@@ -9,7 +9,7 @@ pub enum GameControlFlow {
 pub enum PhaseBreak {
     EndPhase,
     EndTurn,
-    EndGame(GameResult),
+    EndGame(GameResult, GameEndReason),
 }
 
 impl<TOk, TErr> FromResidual<PhaseBreak> for Result<TOk, TErr>
@@ -1,19 +1,25 @@
-use crate::game::states::GameState;
-use crate::game::{filter_event, filter_state, GameError};
+use crate::game::GameError;
 use crate::game_watcher::{GameObserver, NotifyFailedError};
+use furuyoni_lib::rules::deck_list::DeckList;
 use furuyoni_lib::rules::events::GameEvent;
+use furuyoni_lib::rules::states::{filter_event, filter_state, GameState, PlayersData, StateView};
 use furuyoni_lib::rules::ObservePosition;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 
 pub(crate) struct GameRecorder {
     initial_game_state: GameState,
+    seed: u64,
+    deck_lists: PlayersData<DeckList>,
     inner: Mutex<RecorderInner>,
 }
 
 #[derive(Debug)]
 pub(crate) struct RecordedGame {
     pub initial_game_state: GameState,
+    /// The seed of the game's random number generator.
+    pub seed: u64,
+    pub deck_lists: PlayersData<DeckList>,
     pub recorded_events: Vec<GameEvent>,
 }
 
@@ -29,10 +35,16 @@ struct ObserverWithPos {
 }
 
 impl GameRecorder {
-    pub(super) fn new(initial_game_state: GameState) -> Self {
+    pub(super) fn new(
+        initial_game_state: GameState,
+        seed: u64,
+        deck_lists: PlayersData<DeckList>,
+    ) -> Self {
         let current_state = initial_game_state.clone();
         Self {
             initial_game_state,
+            seed,
+            deck_lists,
             inner: Mutex::new(RecorderInner {
                 current_state,
                 recorded_events: vec![],
@@ -58,16 +70,23 @@ impl GameRecorder {
         Ok(())
     }
 
+    /// Return the current state of the game, as viewed from the given position.
+    pub fn state_view(&self, position: ObservePosition) -> StateView {
+        filter_state(position, &self.inner.lock().unwrap().current_state)
+    }
+
     pub fn into_recorded_game(self) -> RecordedGame {
         RecordedGame {
             initial_game_state: self.initial_game_state,
+            seed: self.seed,
+            deck_lists: self.deck_lists,
             recorded_events: self.inner.into_inner().unwrap().recorded_events,
         }
     }
 }
 
 pub(super) async fn run_recorder(
-    mut rx: mpsc::Receiver<GameEvent>,
+    mut rx: mpsc::UnboundedReceiver<GameEvent>,
     recorder: Arc<GameRecorder>,
 ) -> Result<(), GameError> {
     while let Some(event) = rx.recv().await {
@@ -79,10 +98,15 @@ pub(super) async fn run_recorder(
 
         recorded_events.push(event);
 
-        for ObserverWithPos { position, observer } in observers {
-            // ignore notify errors.
-            // Todo: remove observer if error occurs?
-            let _ = observer.notify_event(filter_event(&current_state, *position, event)?);
+        // Drop the observers that can't be notified anymore, such as spectators who have left.
+        let mut unreachable = vec![];
+        for (index, ObserverWithPos { position, observer }) in observers.iter_mut().enumerate() {
+            if observer.notify_event(filter_event(&current_state, *position, event)?).is_err() {
+                unreachable.push(index);
+            }
+        }
+        for index in unreachable.into_iter().rev() {
+            observers.remove(index);
         }
 
         match event {
@@ -0,0 +1,70 @@
+use crate::game::game_recorder::GameRecorder;
+use furuyoni_lib::net::frames::{ GameToPlayerResponse, PlayerToGameRequest };
+use furuyoni_lib::net::message_channel::MessageChannel;
+use furuyoni_lib::rules::{ ObservePosition, PlayerPos };
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+type ResponderT = MessageChannel<GameToPlayerResponse, PlayerToGameRequest>;
+
+/// Answer the requests a player sends on their own, regardless of what the game is waiting for.
+/// When the channel is closed, continues with the channel of the reconnected player. Returns the
+/// channel of the player once the game has ended, that is once it stops taking surrenders.
+pub(crate) async fn handle_player_requests(
+    player: PlayerPos,
+    mut responder: ResponderT,
+    mut reconnect_rx: mpsc::Receiver<ResponderT>,
+    recorder: Arc<GameRecorder>,
+    surrender_tx: mpsc::Sender<PlayerPos>
+) -> ResponderT {
+    loop {
+        tokio::select! {
+            _ = answer_requests(player, &mut responder, &recorder, &surrender_tx) => {}
+            _ = surrender_tx.closed() => {
+                break;
+            }
+        }
+
+        // Once the player can't reconnect anymore, only the end of the game is left to wait for.
+        tokio::select! {
+            Some(new_responder) = reconnect_rx.recv() => {
+                responder = new_responder;
+            }
+            _ = surrender_tx.closed() => {
+                break;
+            }
+        }
+    }
+
+    // The player may have reconnected since the last request.
+    while let Ok(new_responder) = reconnect_rx.try_recv() {
+        responder = new_responder;
+    }
+
+    responder
+}
+
+/// Returns when the channel is closed.
+async fn answer_requests(
+    player: PlayerPos,
+    responder: &mut ResponderT,
+    recorder: &GameRecorder,
+    surrender_tx: &mpsc::Sender<PlayerPos>
+) {
+    while let Ok(request) = responder.receive().await {
+        let response = match request {
+            // The recorded state may lag behind the game by the events not yet recorded.
+            PlayerToGameRequest::RequestState =>
+                GameToPlayerResponse::State(recorder.state_view(ObservePosition::RelativeTo(player))),
+            PlayerToGameRequest::RequestSurrender => {
+                // The game may have already ended.
+                let _ = surrender_tx.send(player).await;
+                GameToPlayerResponse::Ack
+            }
+        };
+
+        if responder.send(response).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::game::{ Game, GameError, GET_ACTION_RETRY_TIMES };
+use crate::players::Player;
+use furuyoni_lib::rules::cards::{ CardSelector, CardSelectorCase, CardsPosition };
+use furuyoni_lib::rules::player_actions::HandSelector;
+use furuyoni_lib::rules::states::{ filter_state, PlayersData, StateView };
+use furuyoni_lib::rules::{ ObservePosition, PlayerPos };
+use std::ops::DerefMut;
+use tokio::join;
+
+const OPENING_HAND_SIZE: usize = 3;
+
+impl Game {
+    /// Shuffle the decks and draw the opening hands, then let each player mulligan once.
+    pub(super) async fn run_game_setup(&mut self) -> Result<(), GameError> {
+        for player in [PlayerPos::P1, PlayerPos::P2] {
+            self.shuffle_deck(player)?;
+            for _ in 0..OPENING_HAND_SIZE {
+                self.draw_card(player)?;
+            }
+        }
+
+        let mulligans = self.request_mulligans().await?;
+
+        for player in [PlayerPos::P1, PlayerPos::P2] {
+            self.mulligan(player, &mulligans[player])?;
+        }
+
+        Ok(())
+    }
+
+    /// Ask both players for the cards to mulligan at the same time.
+    async fn request_mulligans(&mut self) -> Result<PlayersData<Vec<HandSelector>>, GameError> {
+        async fn request_mulligan(
+            p: &mut (impl Player + ?Sized + Send),
+            pos: PlayerPos,
+            state: &StateView,
+            hand_len: usize
+        ) -> Result<Vec<HandSelector>, GameError> {
+            let mut cnt_try = 0;
+            loop {
+                let mut selected = p
+                    .mulligan(state).await
+                    .map_err(|e| GameError::from_player_error(pos, e))?;
+
+                let selected_len = selected.len();
+                selected.sort_by_key(|HandSelector(i)| *i);
+                selected.dedup();
+                if
+                    selected.len() == selected_len &&
+                    selected.iter().all(|HandSelector(i)| *i < hand_len)
+                {
+                    return Ok(selected);
+                }
+                p
+                    .notify_rejection("Select different cards in the hand.")
+                    .map_err(|_| GameError::PlayerCommunicationFail(pos))?;
+                cnt_try += 1;
+                if cnt_try >= GET_ACTION_RETRY_TIMES {
+                    return Err(GameError::InvalidActionRequested(pos));
+                }
+            }
+        }
+
+        let states = PlayersData::new(
+            filter_state(ObservePosition::RelativeTo(PlayerPos::P1), &self.state),
+            filter_state(ObservePosition::RelativeTo(PlayerPos::P2), &self.state)
+        );
+        let hand_lens = PlayersData::new(
+            self.state.player_states[PlayerPos::P1].hand.len(),
+            self.state.player_states[PlayerPos::P2].hand.len()
+        );
+
+        let PlayersData { p1_data, p2_data } = &mut self.players;
+
+        let (a, b) = join!(
+            request_mulligan(
+                p1_data.deref_mut(),
+                PlayerPos::P1,
+                &states[PlayerPos::P1],
+                hand_lens[PlayerPos::P1]
+            ),
+            request_mulligan(
+                p2_data.deref_mut(),
+                PlayerPos::P2,
+                &states[PlayerPos::P2],
+                hand_lens[PlayerPos::P2]
+            )
+        );
+
+        Ok(PlayersData::new(a?, b?))
+    }
+
+    /// Put the selected cards to the bottom of the deck, then draw the same number of cards.
+    /// The selectors should be sorted and distinct.
+    fn mulligan(&mut self, player: PlayerPos, selected: &[HandSelector]) -> Result<(), GameError> {
+        // Move from the back so that the remaining selectors stay valid.
+        for HandSelector(index) in selected.iter().rev() {
+            self.transfer_card(
+                CardSelector {
+                    position: CardsPosition::Hand(player),
+                    case: CardSelectorCase::Index { index: *index },
+                },
+                CardSelector {
+                    position: CardsPosition::Deck(player),
+                    case: CardSelectorCase::First,
+                }
+            )?;
+        }
+
+        for _ in 0..selected.len() {
+            self.draw_card(player)?;
+        }
+
+        Ok(())
+    }
+}
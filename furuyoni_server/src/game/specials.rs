@@ -0,0 +1,45 @@
+use crate::game::game_controlflow::GameControlFlow;
+use crate::game::{ Game, GameError };
+use furuyoni_lib::rules::cards::{ CardSelector, CardSelectorCase, CardType, CardsPosition };
+use furuyoni_lib::rules::states::PetalsPosition;
+use furuyoni_lib::rules::PlayerPos;
+
+impl Game {
+    /// Pay the flare cost of a special card. The paid petals go to the dust.
+    pub(super) fn pay_flare(
+        &mut self,
+        player: PlayerPos,
+        flare_cost: u32
+    ) -> Result<GameControlFlow, GameError> {
+        self.transfer_petals(PetalsPosition::Flare(player), PetalsPosition::Dust, flare_cost)
+    }
+
+    /// Return the used special cards whose recur condition holds for their owner to unused.
+    pub(super) fn return_recurring_specials(&mut self) -> Result<(), GameError> {
+        for player in [PlayerPos::P1, PlayerPos::P2] {
+            // Iterate from the back so that the remaining indices stay valid.
+            for index in (0..self.state.player_states[player].used_specials.len()).rev() {
+                let card = self.state.player_states[player].used_specials[index];
+                let CardType::Special { recur: Some(condition), .. } = card.data().card_type else {
+                    continue;
+                };
+                if !self.check_condition(player, condition) {
+                    continue;
+                }
+
+                self.transfer_card(
+                    CardSelector {
+                        position: CardsPosition::UsedSpecials(player),
+                        case: CardSelectorCase::Index { index },
+                    },
+                    CardSelector {
+                        position: CardsPosition::Specials(player),
+                        case: CardSelectorCase::PushLast,
+                    }
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +0,0 @@
-mod game_state;
-pub(crate) mod player_state;
-
-pub(crate) use game_state::*;
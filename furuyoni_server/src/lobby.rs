@@ -0,0 +1,791 @@
+use crate::clock::TokioClock;
+use crate::game::{create_game, default_deck_list, handle_player_requests, GameRecorder};
+use crate::main_channels::{
+    game_channels_relay, GameChannels, GameChannelsReceiver, GameChannelsSender, GameRequester,
+    GameResponder, LobbyNotifier,
+};
+use crate::sessions::SessionRegistry;
+use crate::players::{
+    DecisionLog, RecordingPlayer, RemotePlayer, TimeControl, TimedPlayer, TimeoutPolicy,
+};
+use crate::replays::{replay_of, ReplayArchive};
+use crate::spectator::Spectator;
+use furuyoni_lib::net::frames::{
+    LobbyGameInfo, LobbyRoomInfo, LobbyToPlayerNotification, LobbyToPlayerResponse, PlayerIdentity,
+    PlayerToLobbyCreateRoom, PlayerToLobbyRequest, PlayerToLobbySpectate,
+    PlayerToLobbyTryEnterRoom, SessionId,
+};
+use furuyoni_lib::rules::states::PlayersData;
+use furuyoni_lib::rules::{GameResult, ObservePosition, PlayerPos};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+
+mod matchmaking;
+
+/// The time control of the games, unless their room has changed it.
+const DEFAULT_TIME_CONTROL: TimeControl = TimeControl {
+    per_decision: Some(Duration::from_secs(30)),
+    time_bank: Some(Duration::from_secs(300)),
+    on_timeout: TimeoutPolicy::DefaultAction,
+};
+
+const MAX_PLAYER_NUM: u32 = 2;
+
+/// How long a disconnected player's seat in a game is kept for the player to reconnect.
+const RECONNECT_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+pub(crate) type RoomId = u32;
+pub(crate) type GameId = u32;
+
+#[derive(Error, Debug)]
+pub(crate) enum LobbyError {
+    #[error("The room doesn't exist.")]
+    RoomNotFound,
+    #[error("The room is full.")]
+    RoomFull,
+    #[error("The player is already in a room.")]
+    AlreadyInRoom,
+    #[error("The player is not in a room.")]
+    NotInRoom,
+    #[error("The player is already playing a game.")]
+    InGame,
+    #[error("The player is already waiting for a match.")]
+    AlreadyQueued,
+    #[error("The player is not waiting for a match.")]
+    NotQueued,
+    #[error("The session has already been started.")]
+    AlreadyStarted,
+    #[error("The session doesn't exist.")]
+    SessionNotFound,
+    #[error("The game doesn't exist or has ended.")]
+    GameNotFound,
+}
+
+pub(crate) struct Lobby {
+    sessions: Arc<SessionRegistry>,
+    inner: Mutex<LobbyInner>,
+}
+
+struct LobbyInner {
+    next_room_id: RoomId,
+    clients: HashMap<SessionId, Client>,
+    rooms: BTreeMap<RoomId, Room>,
+    /// The clients waiting for a match, in the order they have asked.
+    queue: VecDeque<SessionId>,
+    next_game_id: GameId,
+    games: BTreeMap<GameId, RunningGame>,
+    replays: Arc<ReplayArchive>,
+}
+
+struct Client {
+    identity: PlayerIdentity,
+    notifier: LobbyNotifier,
+    /// Taken by the game when the client's game starts, and given back when it ends.
+    game_channels: Option<GameChannels>,
+    room: Option<RoomId>,
+    /// Hands the channels over to the client's game when the client reconnects.
+    game: Option<GameChannelsSender>,
+    connected: bool,
+    /// Counts the connections of the session, to tell whether it has reconnected.
+    connection: u32,
+    /// Dropped to close the connection, once another connection takes over the session.
+    close_connection: oneshot::Sender<()>,
+}
+
+impl Client {
+    fn is_in_game(&self) -> bool {
+        self.game.as_ref().is_some_and(|game| !game.is_closed())
+    }
+}
+
+/// A player whose game is about to start.
+struct MatchedPlayer {
+    client: SessionId,
+    identity: PlayerIdentity,
+    channels: GameChannels,
+    reconnect: GameChannelsReceiver,
+}
+
+struct RunningGame {
+    players: PlayersData<PlayerIdentity>,
+    /// Hands the clients who want to watch over to the game. Closed once the game has ended.
+    spectators: mpsc::UnboundedSender<NewSpectator>,
+}
+
+struct NewSpectator {
+    client: SessionId,
+    channels: GameChannels,
+    delay: Duration,
+}
+
+struct Room {
+    name: String,
+    description: String,
+    seats: PlayersData<Option<Seat>>,
+    time_control: TimeControl,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Seat {
+    client: SessionId,
+    ready: bool,
+}
+
+impl Lobby {
+    pub fn new(sessions: Arc<SessionRegistry>, replays: Arc<ReplayArchive>) -> Self {
+        Self {
+            sessions,
+            inner: Mutex::new(LobbyInner {
+                next_room_id: 0,
+                clients: HashMap::new(),
+                rooms: BTreeMap::new(),
+                queue: VecDeque::new(),
+                next_game_id: 0,
+                games: BTreeMap::new(),
+                replays,
+            }),
+        }
+    }
+
+    /// Register the client of a newly started session.
+    pub fn connect(
+        &self,
+        session_id: SessionId,
+        identity: PlayerIdentity,
+        game_channels: GameChannels,
+        notifier: LobbyNotifier,
+        close_connection: oneshot::Sender<()>,
+    ) {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.clients.insert(
+            session_id,
+            Client {
+                identity,
+                notifier,
+                game_channels: Some(game_channels),
+                room: None,
+                game: None,
+                connected: true,
+                connection: 0,
+                close_connection,
+            },
+        );
+    }
+
+    /// Attach the new connection of a client. A client in a game takes back its seat. If the old
+    /// connection hasn't been noticed dead yet, it is closed and treated as dropped.
+    pub fn reconnect(
+        &self,
+        session_id: SessionId,
+        game_channels: GameChannels,
+        notifier: LobbyNotifier,
+        close_connection: oneshot::Sender<()>,
+    ) -> Result<(), LobbyError> {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner
+            .clients
+            .get(&session_id)
+            .ok_or(LobbyError::SessionNotFound)?
+            .connected
+        {
+            // The client may not be in a room or in the queue.
+            let _ = inner.leave_room(session_id);
+            let _ = inner.leave_matchmaking(session_id);
+        }
+
+        let client = inner
+            .clients
+            .get_mut(&session_id)
+            .ok_or(LobbyError::SessionNotFound)?;
+
+        if let Some(game) = &client.game
+            && client.is_in_game()
+        {
+            game.send(game_channels)
+                .map_err(|_| LobbyError::SessionNotFound)?;
+        } else {
+            client.game = None;
+            client.game_channels = Some(game_channels);
+        }
+
+        client.notifier = notifier;
+        client.connected = true;
+        client.connection += 1;
+        // Drops the sender of the old connection, which closes it.
+        client.close_connection = close_connection;
+
+        Ok(())
+    }
+
+    /// Remove the client from its room and the matchmaking queue. A client in a game is kept for
+    /// the grace period to reconnect, and the others are removed from the lobby right away.
+    pub fn disconnect(self: &Arc<Self>, session_id: SessionId) {
+        let mut inner = self.inner.lock().unwrap();
+
+        // The client may not be in a room or in the queue.
+        let _ = inner.leave_room(session_id);
+        let _ = inner.leave_matchmaking(session_id);
+
+        let Some(client) = inner.clients.get_mut(&session_id) else {
+            return;
+        };
+
+        if client.is_in_game() {
+            client.connected = false;
+
+            let lobby = self.clone();
+            let connection = client.connection;
+            tokio::spawn(async move {
+                tokio::time::sleep(RECONNECT_GRACE_PERIOD).await;
+                lobby.expire(session_id, connection);
+            });
+        } else {
+            inner.clients.remove(&session_id);
+            self.sessions.end_session(session_id);
+        }
+    }
+
+    /// End the session if the client hasn't reconnected since the given connection.
+    fn expire(&self, session_id: SessionId, connection: u32) {
+        let mut inner = self.inner.lock().unwrap();
+
+        let Some(client) = inner.clients.get(&session_id) else {
+            return;
+        };
+        if client.connected || client.connection != connection {
+            return;
+        }
+
+        // Dropping the client closes the channels to the game, which gives up on the player.
+        inner.clients.remove(&session_id);
+        self.sessions.end_session(session_id);
+    }
+
+    pub fn handle_request(
+        self: &Arc<Self>,
+        client: SessionId,
+        request: PlayerToLobbyRequest,
+    ) -> LobbyToPlayerResponse {
+        let mut inner = self.inner.lock().unwrap();
+
+        let result = match request {
+            PlayerToLobbyRequest::Handshake(_) => Err(LobbyError::AlreadyStarted),
+            PlayerToLobbyRequest::GetRoomsList => {
+                return LobbyToPlayerResponse::RoomsList(inner.rooms_list());
+            }
+            PlayerToLobbyRequest::CreateRoom(create_room) => {
+                return match inner.create_room(client, create_room) {
+                    Ok(room) => LobbyToPlayerResponse::RoomCreated(room),
+                    Err(e) => LobbyToPlayerResponse::Refused(e.to_string()),
+                };
+            }
+            PlayerToLobbyRequest::TryEnterRoom(PlayerToLobbyTryEnterRoom { room_id }) => {
+                return LobbyToPlayerResponse::RoonEnterSuccess(
+                    inner.enter_room(client, room_id).is_ok(),
+                );
+            }
+            PlayerToLobbyRequest::LeaveRoom => inner.leave_room(client),
+            PlayerToLobbyRequest::Ready(ready) => inner.set_ready(self, client, ready),
+            PlayerToLobbyRequest::JoinMatchmaking => inner.join_matchmaking(self, client),
+            PlayerToLobbyRequest::LeaveMatchmaking => inner.leave_matchmaking(client),
+            PlayerToLobbyRequest::GetGamesList => {
+                return LobbyToPlayerResponse::GamesList(inner.games_list());
+            }
+            PlayerToLobbyRequest::Spectate(spectate) => inner.spectate(client, spectate),
+        };
+
+        match result {
+            Ok(()) => LobbyToPlayerResponse::Ack,
+            Err(e) => LobbyToPlayerResponse::Refused(e.to_string()),
+        }
+    }
+
+    /// Forget the game, so that no one else can start watching it.
+    fn end_game(&self, game_id: GameId) {
+        self.inner.lock().unwrap().games.remove(&game_id);
+    }
+
+    /// Tell the client that its game couldn't start.
+    fn cancel_game(&self, client: SessionId, reason: String) {
+        let inner = self.inner.lock().unwrap();
+
+        // The client may have left.
+        let _ = inner.notify(client, LobbyToPlayerNotification::GameCancelled(reason));
+    }
+
+    /// Give the game channels back to the client once its game has ended, so that it can play or
+    /// watch another game.
+    fn return_game_channels(&self, session_id: SessionId, channels: GameChannels) {
+        let mut inner = self.inner.lock().unwrap();
+
+        let Some(client) = inner.clients.get_mut(&session_id) else {
+            return;
+        };
+        // The client has reconnected since the game ended, with channels of its own.
+        if client.game_channels.is_some() {
+            return;
+        }
+
+        client.game = None;
+        client.game_channels = Some(channels);
+    }
+}
+
+impl LobbyInner {
+    fn rooms_list(&self) -> Vec<LobbyRoomInfo> {
+        self.rooms
+            .iter()
+            .map(|(id, room)| LobbyRoomInfo {
+                room_id: *id,
+                room_name: room.name.clone(),
+                room_description: room.description.clone(),
+                current_player_num: room.seats.iter().filter(|(_, s)| s.is_some()).count() as u32,
+                max_player_num: MAX_PLAYER_NUM,
+            })
+            .collect()
+    }
+
+    /// Return the games that can be watched, forgetting the ones that have ended.
+    fn games_list(&mut self) -> Vec<LobbyGameInfo> {
+        self.games.retain(|_, game| !game.spectators.is_closed());
+
+        self.games
+            .iter()
+            .map(|(id, game)| LobbyGameInfo {
+                game_id: *id,
+                players: game.players.clone(),
+            })
+            .collect()
+    }
+
+    /// Hand the game channels of the client over to the game, which streams itself to the client.
+    fn spectate(
+        &mut self,
+        client_id: SessionId,
+        PlayerToLobbySpectate { game_id, delay }: PlayerToLobbySpectate,
+    ) -> Result<(), LobbyError> {
+        let spectators = self
+            .games
+            .get(&game_id)
+            .map(|game| game.spectators.clone())
+            .filter(|spectators| !spectators.is_closed())
+            .ok_or(LobbyError::GameNotFound)?;
+        let client = self.idle_client(client_id)?;
+        let channels = client.game_channels.take().ok_or(LobbyError::InGame)?;
+
+        let spectator = NewSpectator {
+            client: client_id,
+            channels,
+            delay: delay.unwrap_or_default(),
+        };
+        if let Err(mpsc::error::SendError(spectator)) = spectators.send(spectator) {
+            // The game has just ended.
+            client.game_channels = Some(spectator.channels);
+            return Err(LobbyError::GameNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Return the client if it can enter a room or the matchmaking queue.
+    fn idle_client(&mut self, client: SessionId) -> Result<&mut Client, LobbyError> {
+        if self.queue.contains(&client) {
+            return Err(LobbyError::AlreadyQueued);
+        }
+        let client = self.clients.get_mut(&client).ok_or(LobbyError::InGame)?;
+        if client.game_channels.is_none() {
+            return Err(LobbyError::InGame);
+        }
+        if client.room.is_some() {
+            return Err(LobbyError::AlreadyInRoom);
+        }
+
+        Ok(client)
+    }
+
+    fn create_room(
+        &mut self,
+        client: SessionId,
+        PlayerToLobbyCreateRoom {
+            room_name,
+            room_description,
+            forfeit_on_timeout,
+        }: PlayerToLobbyCreateRoom,
+    ) -> Result<RoomId, LobbyError> {
+        self.idle_client(client)?;
+
+        let room = self.next_room_id;
+        self.next_room_id += 1;
+        self.rooms.insert(
+            room,
+            Room {
+                name: room_name,
+                description: room_description,
+                seats: PlayersData::new(None, None),
+                time_control: TimeControl {
+                    on_timeout: if forfeit_on_timeout {
+                        TimeoutPolicy::Forfeit
+                    } else {
+                        TimeoutPolicy::DefaultAction
+                    },
+                    ..DEFAULT_TIME_CONTROL
+                },
+            },
+        );
+        self.enter_room(client, room)?;
+
+        Ok(room)
+    }
+
+    fn enter_room(&mut self, client: SessionId, room: RoomId) -> Result<(), LobbyError> {
+        self.idle_client(client)?;
+
+        let seats = &mut self.rooms.get_mut(&room).ok_or(LobbyError::RoomNotFound)?.seats;
+        let pos = [PlayerPos::P1, PlayerPos::P2]
+            .into_iter()
+            .find(|pos| seats[pos].is_none())
+            .ok_or(LobbyError::RoomFull)?;
+        seats[pos] = Some(Seat {
+            client,
+            ready: false,
+        });
+
+        self.idle_client(client)?.room = Some(room);
+
+        Ok(())
+    }
+
+    /// Leave the room. The room is closed when it becomes empty.
+    fn leave_room(&mut self, client_id: SessionId) -> Result<(), LobbyError> {
+        let client = self.clients.get_mut(&client_id).ok_or(LobbyError::NotInRoom)?;
+        let room_id = client.room.take().ok_or(LobbyError::NotInRoom)?;
+
+        let room = self.rooms.get_mut(&room_id).ok_or(LobbyError::RoomNotFound)?;
+        for pos in [PlayerPos::P1, PlayerPos::P2] {
+            if let Some(Seat { client, .. }) = room.seats[pos]
+                && client == client_id
+            {
+                room.seats[pos] = None;
+            }
+        }
+
+        if room.seats.iter().all(|(_, s)| s.is_none()) {
+            self.rooms.remove(&room_id);
+        }
+
+        Ok(())
+    }
+
+    /// Set whether the client is ready, then start the game if everyone in the room is ready.
+    fn set_ready(
+        &mut self,
+        lobby: &Arc<Lobby>,
+        client_id: SessionId,
+        ready: bool,
+    ) -> Result<(), LobbyError> {
+        let room_id = self
+            .clients
+            .get(&client_id)
+            .and_then(|c| c.room)
+            .ok_or(LobbyError::NotInRoom)?;
+        let room = self.rooms.get_mut(&room_id).ok_or(LobbyError::RoomNotFound)?;
+
+        for (_, seat) in room.seats.iter_mut() {
+            if let Some(seat) = seat
+                && seat.client == client_id
+            {
+                seat.ready = ready;
+            }
+        }
+
+        let (Some(p1), Some(p2)) = (room.seats.p1_data, room.seats.p2_data) else {
+            return Ok(());
+        };
+        if !(p1.ready && p2.ready) {
+            return Ok(());
+        }
+
+        let time_control = room.time_control;
+        self.start_game(lobby, PlayersData::new(p1.client, p2.client), time_control)?;
+
+        // The room is only closed once its game has started, so that it is kept on failure.
+        self.rooms.remove(&room_id);
+        for client in [p1.client, p2.client] {
+            if let Some(client) = self.clients.get_mut(&client) {
+                client.room = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take over the game channels of the clients and spawn their game.
+    fn start_game(
+        &mut self,
+        lobby: &Arc<Lobby>,
+        clients: PlayersData<SessionId>,
+        time_control: TimeControl,
+    ) -> Result<(), LobbyError> {
+        // Check both clients before taking the channels of either.
+        for (_, client) in clients.iter() {
+            self.clients
+                .get(client)
+                .and_then(|client| client.game_channels.as_ref())
+                .ok_or(LobbyError::InGame)?;
+        }
+
+        let mut take_player = |client_id| -> Result<MatchedPlayer, LobbyError> {
+            let client = self.clients.get_mut(&client_id).ok_or(LobbyError::InGame)?;
+            let channels = client.game_channels.take().ok_or(LobbyError::InGame)?;
+
+            let (game, reconnect) = game_channels_relay();
+            client.game = Some(game);
+
+            Ok(MatchedPlayer {
+                client: client_id,
+                identity: client.identity.clone(),
+                channels,
+                reconnect,
+            })
+        };
+        let players = PlayersData::new(
+            take_player(clients.p1_data)?,
+            take_player(clients.p2_data)?,
+        );
+
+        let (spectators, spectators_rx) = mpsc::unbounded_channel();
+        let game_id = self.next_game_id;
+        self.next_game_id += 1;
+        self.games.insert(
+            game_id,
+            RunningGame {
+                players: PlayersData::new(
+                    players.p1_data.identity.clone(),
+                    players.p2_data.identity.clone(),
+                ),
+                spectators,
+            },
+        );
+
+        tokio::spawn(run_game(
+            lobby.clone(),
+            game_id,
+            players,
+            time_control,
+            spectators_rx,
+            self.replays.clone(),
+        ));
+
+        Ok(())
+    }
+}
+
+/// Run a game between two remote players, then report the result and archive the replay. The
+/// game channels of the players and spectators are given back to the lobby once they are done.
+async fn run_game(
+    lobby: Arc<Lobby>,
+    game_id: GameId,
+    players: PlayersData<MatchedPlayer>,
+    time_control: TimeControl,
+    spectators_rx: mpsc::UnboundedReceiver<NewSpectator>,
+    replays: Arc<ReplayArchive>,
+) {
+    let PlayersData {
+        p1_data:
+            MatchedPlayer {
+                client: p1_client,
+                identity: p1_identity,
+                channels: p1_channels,
+                reconnect: p1_reconnect,
+            },
+        p2_data:
+            MatchedPlayer {
+                client: p2_client,
+                identity: p2_identity,
+                channels: p2_channels,
+                reconnect: p2_reconnect,
+            },
+    } = players;
+
+    // The remote players give their requesters back once the game has dropped them.
+    let (p1_requester_tx, p1_requester_rx) = oneshot::channel();
+    let (p2_requester_tx, p2_requester_rx) = oneshot::channel();
+
+    // The recording players wrap everything else, to record what the game has actually received.
+    let decisions = Arc::new(DecisionLog::new());
+    let p1 = RecordingPlayer::new(
+        Box::new(TimedPlayer::new(
+            Box::new(RemotePlayer::new(
+                p1_channels.requester,
+                p1_reconnect.requester_rx,
+                p1_requester_tx,
+            )),
+            time_control,
+            TokioClock,
+        )),
+        PlayerPos::P1,
+        decisions.clone(),
+        TokioClock,
+    );
+    let p2 = RecordingPlayer::new(
+        Box::new(TimedPlayer::new(
+            Box::new(RemotePlayer::new(
+                p2_channels.requester,
+                p2_reconnect.requester_rx,
+                p2_requester_tx,
+            )),
+            time_control,
+            TokioClock,
+        )),
+        PlayerPos::P2,
+        decisions.clone(),
+        TokioClock,
+    );
+
+    // Todo: let the players choose their decks.
+    let deck_lists = PlayersData::new(default_deck_list(), default_deck_list());
+
+    let seed = rand::random();
+
+    let (game, recorder) = match create_game(Box::new(p1), Box::new(p2), deck_lists, seed) {
+        Ok(created) => created,
+        Err(e) => {
+            eprintln!("Failed to create the game: {}", e);
+            lobby.end_game(game_id);
+
+            for (client, requester_rx, responder) in [
+                (p1_client, p1_requester_rx, p1_channels.responder),
+                (p2_client, p2_requester_rx, p2_channels.responder),
+            ] {
+                lobby.cancel_game(client, e.to_string());
+                return_game_channels(&lobby, client, requester_rx, responder).await;
+            }
+            return;
+        }
+    };
+
+    let player_requests_tasks = [
+        tokio::spawn(handle_player_requests(
+            PlayerPos::P1,
+            p1_channels.responder,
+            p1_reconnect.responder_rx,
+            recorder.clone(),
+            game.surrender_sender(),
+        )),
+        tokio::spawn(handle_player_requests(
+            PlayerPos::P2,
+            p2_channels.responder,
+            p2_reconnect.responder_rx,
+            recorder.clone(),
+            game.surrender_sender(),
+        )),
+    ];
+
+    let spectators_task = tokio::spawn(add_spectators(
+        spectators_rx,
+        recorder.clone(),
+        lobby.clone(),
+    ));
+
+    let outcome = game.run().await;
+
+    // Closes the channel of the spectators, which ends the task once the last ones are added.
+    lobby.end_game(game_id);
+
+    // The tasks hold references to the recorder. The player requests tasks end along with the
+    // game and return the responders, while the players have given back the requesters.
+    let [p1_responder, p2_responder] = player_requests_tasks;
+    let (p1_responder, p2_responder, _) = tokio::join!(p1_responder, p2_responder, spectators_task);
+
+    for (client, requester_rx, responder) in [
+        (p1_client, p1_requester_rx, p1_responder),
+        (p2_client, p2_requester_rx, p2_responder),
+    ] {
+        // The task only fails if it has panicked.
+        if let Ok(responder) = responder {
+            return_game_channels(&lobby, client, requester_rx, responder).await;
+        }
+    }
+
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            eprintln!("The game has ended with an error: {}", e);
+            return;
+        }
+    };
+
+    let recorded = Arc::into_inner(recorder).unwrap().into_recorded_game();
+
+    let winner_str = match outcome.result {
+        GameResult::Draw => "Draw",
+        GameResult::Winner(winner) => match winner {
+            PlayerPos::P1 => p1_identity.display_name.as_str(),
+            PlayerPos::P2 => p2_identity.display_name.as_str(),
+        },
+    };
+    println!(
+        "Game ended. Winner: {}, reason: {:?}, summary: {:?}",
+        winner_str, outcome.reason, outcome.summary
+    );
+
+    let replay = replay_of(
+        recorded,
+        PlayersData::new(p1_identity, p2_identity),
+        decisions.take(),
+    );
+    match replays.save(game_id, &replay).await {
+        Ok(path) => println!("Replay saved to {}", path.display()),
+        Err(e) => eprintln!("Failed to save the replay: {}", e),
+    }
+}
+
+/// Let the clients who have asked to watch the game see it as bystanders. Ends once the lobby
+/// has forgotten the game.
+async fn add_spectators(
+    mut spectators_rx: mpsc::UnboundedReceiver<NewSpectator>,
+    recorder: Arc<GameRecorder>,
+    lobby: Arc<Lobby>,
+) {
+    while let Some(NewSpectator {
+        client,
+        channels,
+        delay,
+    }) = spectators_rx.recv().await
+    {
+        let (requester_tx, requester_rx) = oneshot::channel();
+        let spectator = Spectator::new(channels.requester, delay, requester_tx);
+
+        // The client may have left already.
+        let _ = recorder.add_observer(ObservePosition::ByStander, spectator);
+
+        // The spectator is dropped along with the recorder, once the game has ended.
+        let lobby = lobby.clone();
+        tokio::spawn(async move {
+            return_game_channels(&lobby, client, requester_rx, channels.responder).await;
+        });
+    }
+}
+
+/// Give the game channels back to the client once the game has let go of the requester.
+async fn return_game_channels(
+    lobby: &Lobby,
+    client: SessionId,
+    requester_rx: oneshot::Receiver<GameRequester>,
+    responder: GameResponder,
+) {
+    if let Ok(requester) = requester_rx.await {
+        lobby.return_game_channels(
+            client,
+            GameChannels {
+                requester,
+                responder,
+            },
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use crate::lobby::{Lobby, LobbyError, LobbyInner, DEFAULT_TIME_CONTROL};
+use furuyoni_lib::net::frames::{LobbyToPlayerNotification, LobbyToPlayerRequest, SessionId};
+use furuyoni_lib::net::message_sender::MessageSendError;
+use furuyoni_lib::rules::states::PlayersData;
+use std::sync::Arc;
+
+impl LobbyInner {
+    /// Put the client at the end of the matchmaking queue, then pair the waiting players.
+    pub(super) fn join_matchmaking(
+        &mut self,
+        lobby: &Arc<Lobby>,
+        client: SessionId,
+    ) -> Result<(), LobbyError> {
+        self.idle_client(client)?;
+        self.queue.push_back(client);
+
+        // Drop the unreachable clients before they get matched.
+        self.notify_queue_status();
+        self.match_players(lobby);
+        self.notify_queue_status();
+
+        Ok(())
+    }
+
+    pub(super) fn leave_matchmaking(&mut self, client: SessionId) -> Result<(), LobbyError> {
+        let index = self
+            .queue
+            .iter()
+            .position(|c| *c == client)
+            .ok_or(LobbyError::NotQueued)?;
+        self.queue.remove(index);
+
+        self.notify_queue_status();
+
+        Ok(())
+    }
+
+    /// Pair the players in the order they have joined the queue.
+    // Todo: pair by rating.
+    fn match_players(&mut self, lobby: &Arc<Lobby>) {
+        while self.queue.len() >= 2 {
+            let (Some(p1), Some(p2)) = (self.queue.pop_front(), self.queue.pop_front()) else {
+                unreachable!()
+            };
+
+            match self.start_game(lobby, PlayersData::new(p1, p2), DEFAULT_TIME_CONTROL) {
+                Ok(()) => {
+                    for client in [p1, p2] {
+                        // The game will find out if the client is gone.
+                        let _ = self.notify(client, LobbyToPlayerNotification::MatchFound);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to start a matched game: {}", e);
+
+                    // Give the client who can still play its place back. It is told its position
+                    // by the queue status that follows the matching.
+                    for client in [p2, p1] {
+                        if self
+                            .clients
+                            .get(&client)
+                            .is_some_and(|client| client.game_channels.is_some())
+                        {
+                            self.queue.push_front(client);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Report each waiting client's position, removing the ones that can't be reached anymore.
+    fn notify_queue_status(&mut self) {
+        loop {
+            let queue_len = self.queue.len() as u32;
+            let unreachable = self.queue.iter().enumerate().position(|(position, client)| {
+                let status = LobbyToPlayerNotification::QueueStatus {
+                    position: position as u32,
+                    queue_len,
+                };
+                self.notify(*client, status).is_err()
+            });
+
+            match unreachable {
+                Some(index) => {
+                    self.queue.remove(index);
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub(super) fn notify(
+        &self,
+        client: SessionId,
+        notification: LobbyToPlayerNotification,
+    ) -> Result<(), MessageSendError> {
+        self.clients
+            .get(&client)
+            .ok_or(MessageSendError::ChannelClosed)?
+            .notifier
+            .send(LobbyToPlayerRequest::Notify(notification))
+    }
+}
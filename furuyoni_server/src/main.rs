@@ -3,31 +3,48 @@
 #![feature(let_chains)]
 extern crate furuyoni_lib;
 
+mod clock;
 mod game;
 
 mod game_watcher;
+mod lobby;
 mod main_channels;
 mod networking;
 pub mod players;
+mod replays;
+mod resimulation;
+mod sessions;
+mod spectator;
 
 use crate::furuyoni_lib::net::message_sender::IntoMessageMap;
 use furuyoni_lib::net::frames::*;
 use furuyoni_lib::net::message_channel::MessageChannel;
-use furuyoni_lib::rules::{GameResult, PlayerPos};
-use players::{IdlePlayer, RemotePlayer};
 use std::sync::Arc;
 
 use networking::{post_office, ServerConnectionReader, ServerConnectionWriter};
 
-use crate::game::{create_game, GameSetup};
+use crate::lobby::Lobby;
+use crate::main_channels::GameChannels;
+use crate::replays::ReplayArchive;
+use crate::sessions::SessionRegistry;
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio::task::JoinHandle;
 
 #[tokio::main]
 async fn main() {
+    if let Some(directory) = verify_replays_directory() {
+        let all_reproduced = resimulation::verify_replays(directory)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to read the replays: {}", e));
+        std::process::exit(if all_reproduced { 0 } else { 1 });
+    }
+
     println!("Hello, world!");
     let listener = TcpListener::bind("127.0.0.1:4255").await.unwrap();
+    let sessions = Arc::new(SessionRegistry::new());
+    let replays = Arc::new(ReplayArchive::new(replay_directory()));
+    let lobby = Arc::new(Lobby::new(sessions.clone(), replays));
 
     loop {
         println!("Ready To Get New Connection!");
@@ -36,50 +53,143 @@ async fn main() {
 
         println!("New Connection Started: {addr}");
 
+        let lobby = lobby.clone();
+        let sessions = sessions.clone();
         tokio::spawn(async move {
-            main_server_component(socket).await;
+            main_server_component(socket, lobby, sessions).await;
         });
     }
 }
 
-async fn main_server_component(socket: TcpStream) {
-    // Game Action
-    // TODO: Get Player List And Put Two Players in to Spawn Game
-    spawn_game(socket).await;
+/// The directory to keep the replays of the finished games in, given by `--replay-dir DIR`.
+fn replay_directory() -> String {
+    let mut args = std::env::args();
+    args.position(|arg| arg == "--replay-dir")
+        .and_then(|_| args.next())
+        .unwrap_or_else(|| "replays".to_string())
 }
 
-async fn spawn_game(socket: TcpStream) {
+/// The directory of the replays to play again and compare with their recordings, given by
+/// `--verify-replays DIR`, instead of running the server.
+fn verify_replays_directory() -> Option<String> {
+    let mut args = std::env::args();
+    args.position(|arg| arg == "--verify-replays")
+        .and_then(|_| args.next())
+}
+
+async fn main_server_component(
+    socket: TcpStream,
+    lobby: Arc<Lobby>,
+    sessions: Arc<SessionRegistry>,
+) {
     let (
         lobby_to_player_requester,
-        lobby_to_player_responder,
+        mut lobby_to_player_responder,
         game_to_player_requester,
         game_to_player_responder,
         post_office_task,
     ) = spawn_post_office(socket);
-    let p1 = RemotePlayer::new(game_to_player_requester);
-    let p2 = IdlePlayer {};
 
-    let (game, recorder) = create_game(Box::new(p1), Box::new(p2));
+    let game_channels = GameChannels {
+        requester: game_to_player_requester,
+        responder: game_to_player_responder,
+    };
+    let (close_tx, mut close_rx) = oneshot::channel();
 
-    let game_res = game
-        .run()
-        .await
-        .unwrap_or_else(|e| panic!("todo: handle game run errors: {}", e));
+    let Some(session_id) = handshake(
+        &mut lobby_to_player_responder,
+        &sessions,
+        &lobby,
+        game_channels,
+        lobby_to_player_requester,
+        close_tx,
+    )
+    .await
+    else {
+        post_office_task.abort();
+        return;
+    };
 
-    let recorded = Arc::into_inner(recorder).unwrap().into_recorded_game();
+    // The game is started by the lobby once the client's room is ready or a match is found.
+    loop {
+        let request = tokio::select! {
+            request = lobby_to_player_responder.receive() => request,
+            _ = &mut close_rx => {
+                // Another connection has taken over the session.
+                post_office_task.abort();
+                return;
+            }
+        };
+        let Ok(request) = request else {
+            break;
+        };
+
+        let response = lobby.handle_request(session_id, request);
+        if lobby_to_player_responder.send(response).is_err() {
+            break;
+        }
+    }
 
-    let winner_str = match game_res {
-        GameResult::Draw => "Draw",
-        GameResult::Winner(winner) => match winner {
-            PlayerPos::P1 => "P1",
-            PlayerPos::P2 => "P2",
-        },
-    };
-    println!("Game ended. Winner: {}", winner_str);
+    lobby.disconnect(session_id);
 
     post_office_task.abort();
 }
 
+/// Wait for the handshake of the client, then start its session, or resume the session of a
+/// dropped connection.
+async fn handshake(
+    responder: &mut MessageChannel<LobbyToPlayerResponse, PlayerToLobbyRequest>,
+    sessions: &SessionRegistry,
+    lobby: &Lobby,
+    game_channels: GameChannels,
+    notifier: MessageChannel<LobbyToPlayerRequest, PlayerToLobbyResponse>,
+    close_connection: oneshot::Sender<()>,
+) -> Option<SessionId> {
+    let result = match responder.receive().await.ok()? {
+        PlayerToLobbyRequest::Handshake(handshake) => {
+            let resuming = matches!(handshake.credentials, Credentials::Resume(_));
+
+            sessions
+                .start_session(handshake)
+                .map_err(|e| e.to_string())
+                .and_then(|(session_id, identity)| {
+                    if resuming {
+                        lobby
+                            .reconnect(session_id, game_channels, notifier, close_connection)
+                            .map_err(|e| e.to_string())?;
+                    } else {
+                        lobby.connect(
+                            session_id,
+                            identity.clone(),
+                            game_channels,
+                            notifier,
+                            close_connection,
+                        );
+                    }
+                    Ok((session_id, identity))
+                })
+        }
+        _ => Err("The connection should start with a handshake.".to_string()),
+    };
+
+    match result {
+        Ok((session_id, identity)) => {
+            println!("Session started: {:?} as {:?}", session_id, identity);
+
+            // The session is ended by the lobby if the client is gone.
+            let _ = responder.send(LobbyToPlayerResponse::SessionStarted {
+                session_id,
+                identity,
+            });
+            Some(session_id)
+        }
+        Err(reason) => {
+            let _ = responder.send(LobbyToPlayerResponse::Refused(reason));
+            None
+        }
+    }
+}
+
 fn spawn_post_office(
     stream: TcpStream,
 ) -> (
@@ -1,10 +1,69 @@
 extern crate furuyoni_lib;
 
-use furuyoni_lib::net::frames::{GameToPlayerRequest, LobbyToPlayerRequest, PlayerToGameResponse, PlayerToLobbyResponse};
+use furuyoni_lib::net::frames::{
+    GameToPlayerRequest, GameToPlayerResponse, LobbyToPlayerRequest, PlayerToGameRequest,
+    PlayerToGameResponse, PlayerToLobbyResponse,
+};
 use furuyoni_lib::net::message_channel::MessageChannel;
+use furuyoni_lib::net::message_sender::MessageSendError;
+use tokio::sync::mpsc;
 
 pub struct MainChannels {
     lobby_to_player_channel: MessageChannel<LobbyToPlayerRequest, PlayerToLobbyResponse>,
     game_to_player_channel: MessageChannel<GameToPlayerRequest, PlayerToGameResponse>,
 }
 
+pub(crate) type GameRequester = MessageChannel<GameToPlayerRequest, PlayerToGameResponse>;
+pub(crate) type GameResponder = MessageChannel<GameToPlayerResponse, PlayerToGameRequest>;
+
+/// The game channels of a connected client, taken over by the game the client plays.
+pub(crate) struct GameChannels {
+    pub requester: GameRequester,
+    pub responder: GameResponder,
+}
+
+/// Hands the game channels of a reconnected client over to the game the client plays.
+pub(crate) struct GameChannelsSender {
+    requester_tx: mpsc::Sender<GameRequester>,
+    responder_tx: mpsc::Sender<GameResponder>,
+}
+
+pub(crate) struct GameChannelsReceiver {
+    pub requester_rx: mpsc::Receiver<GameRequester>,
+    pub responder_rx: mpsc::Receiver<GameResponder>,
+}
+
+pub(crate) fn game_channels_relay() -> (GameChannelsSender, GameChannelsReceiver) {
+    let (requester_tx, requester_rx) = mpsc::channel(1);
+    let (responder_tx, responder_rx) = mpsc::channel(1);
+
+    (
+        GameChannelsSender {
+            requester_tx,
+            responder_tx,
+        },
+        GameChannelsReceiver {
+            requester_rx,
+            responder_rx,
+        },
+    )
+}
+
+impl GameChannelsSender {
+    pub fn send(&self, channels: GameChannels) -> Result<(), MessageSendError> {
+        self.requester_tx
+            .try_send(channels.requester)
+            .map_err(|_| MessageSendError::ChannelClosed)?;
+        self.responder_tx
+            .try_send(channels.responder)
+            .map_err(|_| MessageSendError::ChannelClosed)
+    }
+
+    /// Whether the game has ended.
+    pub fn is_closed(&self) -> bool {
+        self.requester_tx.is_closed()
+    }
+}
+
+/// The channel to send notifications and requests from the lobby to a connected client.
+pub(crate) type LobbyNotifier = MessageChannel<LobbyToPlayerRequest, PlayerToLobbyResponse>;
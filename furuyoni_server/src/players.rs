@@ -1,8 +1,15 @@
 mod cli_player;
 mod idle_player;
 mod player;
+mod recording_player;
 mod remote_player;
+mod scripted_player;
+mod timed_player;
 
 pub(crate) use {
-    cli_player::CliPlayer, idle_player::IdlePlayer, player::Player, remote_player::RemotePlayer,
+    cli_player::CliPlayer, idle_player::IdlePlayer, player::Player,
+    player::PlayerError, recording_player::DecisionLog, recording_player::RecordingPlayer,
+    remote_player::RemotePlayer, scripted_player::RequestMismatch,
+    scripted_player::ScriptedPlayer, timed_player::TimeControl, timed_player::TimedPlayer,
+    timed_player::TimeoutPolicy,
 };
@@ -1,7 +1,10 @@
-use crate::game_watcher::GameObserver;
+use super::PlayerError;
+use crate::game_watcher::{GameObserver, NotifyFailedError};
 use async_trait::async_trait;
+use furuyoni_lib::rules::attack::{AttackDamage, DamageTarget};
 use furuyoni_lib::rules::player_actions::{
-    BasicAction, BasicActionCost, MainPhaseAction, PlayableCardSelector,
+    BasicAction, BasicActionCost, HandSelector, MainPhaseAction, PlayableCardSelector,
+    ReactionAction,
 };
 use furuyoni_lib::rules::states::*;
 
@@ -12,25 +15,118 @@ impl super::Player for CliPlayer {
     async fn main_phase_action(
         &mut self,
         state: &StateView,
-        _playable_cards: &Vec<PlayableCardSelector>,
+        playable_cards: &Vec<PlayableCardSelector>,
         performable_basic_actions: &Vec<BasicAction>,
         _available_basic_action_costs: &Vec<BasicActionCost>,
-    ) -> Result<MainPhaseAction, ()> {
+    ) -> Result<MainPhaseAction, PlayerError> {
         Self::print_state(&state);
 
+        println!("cards: {playable_cards:?}");
         println!("actions: {performable_basic_actions:?}");
 
-        let index = Self::input_index_lower_than(performable_basic_actions.len() + 1).unwrap();
+        let cards_len = playable_cards.len();
+        let actions_len = performable_basic_actions.len();
+        let index = Self::input_index_lower_than(cards_len + actions_len + 1).unwrap();
 
-        if index == performable_basic_actions.len() {
-            Ok(MainPhaseAction::EndMainPhase)
-        } else {
+        if index < cards_len {
+            Ok(MainPhaseAction::PlayCard(playable_cards[index]))
+        } else if index < cards_len + actions_len {
             Ok(MainPhaseAction::PlayBasicAction {
-                action: performable_basic_actions[index],
+                action: performable_basic_actions[index - cards_len],
                 cost: BasicActionCost::Vigor,
             })
+        } else {
+            Ok(MainPhaseAction::EndMainPhase)
+        }
+    }
+
+    async fn damage_target(
+        &mut self,
+        state: &StateView,
+        damage: AttackDamage,
+        available_targets: &Vec<DamageTarget>,
+    ) -> Result<DamageTarget, PlayerError> {
+        Self::print_state(&state);
+
+        println!("damage: {damage:?}");
+        println!("targets: {available_targets:?}");
+
+        let index = Self::input_index_lower_than(available_targets.len()).unwrap();
+
+        Ok(available_targets[index])
+    }
+
+    async fn reaction(
+        &mut self,
+        state: &StateView,
+        attack_damage: AttackDamage,
+        playable_reactions: &Vec<PlayableCardSelector>,
+    ) -> Result<ReactionAction, PlayerError> {
+        Self::print_state(&state);
+
+        println!("attack: {attack_damage:?}");
+        println!("reactions: {playable_reactions:?}");
+
+        let index = Self::input_index_lower_than(playable_reactions.len() + 1).unwrap();
+
+        if index == playable_reactions.len() {
+            Ok(ReactionAction::Pass)
+        } else {
+            Ok(ReactionAction::PlayReaction(playable_reactions[index]))
         }
     }
+
+    async fn effect_choice(
+        &mut self,
+        state: &StateView,
+        options: &Vec<String>,
+    ) -> Result<usize, PlayerError> {
+        Self::print_state(&state);
+
+        println!("options: {options:?}");
+
+        Ok(Self::input_index_lower_than(options.len()).unwrap())
+    }
+
+    async fn cards_to_discard(
+        &mut self,
+        state: &StateView,
+        amount: usize,
+    ) -> Result<Vec<HandSelector>, PlayerError> {
+        Self::print_state(&state);
+
+        println!("discard {amount} card(s) from the hand.");
+
+        (0..amount)
+            .map(|_| Self::get_input::<usize>().map(HandSelector))
+            .collect::<Result<_, _>>()
+            .map_err(|_| PlayerError::CommunicationFail)
+    }
+
+    fn notify_rejection(&mut self, reason: &str) -> Result<(), NotifyFailedError> {
+        println!("rejected: {reason}");
+        Ok(())
+    }
+
+    async fn mulligan(&mut self, state: &StateView) -> Result<Vec<HandSelector>, PlayerError> {
+        Self::print_state(&state);
+
+        println!("number of cards to mulligan:");
+        let amount = Self::get_input::<usize>().map_err(|_| PlayerError::CommunicationFail)?;
+
+        (0..amount)
+            .map(|_| Self::get_input::<usize>().map(HandSelector))
+            .collect::<Result<_, _>>()
+            .map_err(|_| PlayerError::CommunicationFail)
+    }
+
+    async fn reshuffle(&mut self, state: &StateView) -> Result<bool, PlayerError> {
+        Self::print_state(&state);
+
+        println!("reshuffle the deck? (0: no, 1: yes)");
+
+        Ok(Self::input_index_lower_than(2).unwrap() == 1)
+    }
 }
 
 impl GameObserver for CliPlayer {}
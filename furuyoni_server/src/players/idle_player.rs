@@ -1,7 +1,10 @@
+use super::PlayerError;
 use crate::game_watcher::GameObserver;
 use async_trait::async_trait;
+use furuyoni_lib::rules::attack::{AttackDamage, DamageTarget};
 use furuyoni_lib::rules::player_actions::{
-    BasicAction, BasicActionCost, MainPhaseAction, PlayableCardSelector,
+    BasicAction, BasicActionCost, HandSelector, MainPhaseAction, PlayableCardSelector,
+    ReactionAction,
 };
 use furuyoni_lib::rules::states::*;
 
@@ -15,8 +18,50 @@ impl super::Player for IdlePlayer {
         _playable_cards: &Vec<PlayableCardSelector>,
         _performable_basic_actions: &Vec<BasicAction>,
         _available_basic_action_costs: &Vec<BasicActionCost>,
-    ) -> Result<MainPhaseAction, ()> {
+    ) -> Result<MainPhaseAction, PlayerError> {
         Ok(MainPhaseAction::EndMainPhase)
     }
+
+    async fn damage_target(
+        &mut self,
+        _state: &StateView,
+        _damage: AttackDamage,
+        available_targets: &Vec<DamageTarget>,
+    ) -> Result<DamageTarget, PlayerError> {
+        available_targets.first().copied().ok_or(PlayerError::CommunicationFail)
+    }
+
+    async fn reaction(
+        &mut self,
+        _state: &StateView,
+        _attack_damage: AttackDamage,
+        _playable_reactions: &Vec<PlayableCardSelector>,
+    ) -> Result<ReactionAction, PlayerError> {
+        Ok(ReactionAction::Pass)
+    }
+
+    async fn effect_choice(
+        &mut self,
+        _state: &StateView,
+        _options: &Vec<String>,
+    ) -> Result<usize, PlayerError> {
+        Ok(0)
+    }
+
+    async fn cards_to_discard(
+        &mut self,
+        _state: &StateView,
+        amount: usize,
+    ) -> Result<Vec<HandSelector>, PlayerError> {
+        Ok((0..amount).map(HandSelector).collect())
+    }
+
+    async fn mulligan(&mut self, _state: &StateView) -> Result<Vec<HandSelector>, PlayerError> {
+        Ok(vec![])
+    }
+
+    async fn reshuffle(&mut self, _state: &StateView) -> Result<bool, PlayerError> {
+        Ok(false)
+    }
 }
 impl GameObserver for IdlePlayer {}
@@ -1,11 +1,23 @@
-use crate::game_watcher::GameObserver;
+use crate::game_watcher::{GameObserver, NotifyFailedError};
 use async_trait::async_trait;
+use furuyoni_lib::net::frames::TimeLeft;
+use furuyoni_lib::rules::attack::{AttackDamage, DamageTarget};
 use furuyoni_lib::rules::events::GameEvent;
 use furuyoni_lib::rules::player_actions::{
-    BasicAction, BasicActionCost, MainPhaseAction, PlayableCardSelector,
+    BasicAction, BasicActionCost, HandSelector, MainPhaseAction, PlayableCardSelector,
+    ReactionAction,
 };
 use furuyoni_lib::rules::states::*;
 use furuyoni_lib::rules::PlayerPos;
+use thiserror::Error;
+
+#[derive(Error, Debug, Copy, Clone, PartialEq)]
+pub(crate) enum PlayerError {
+    #[error("Failed to communicate with the player.")]
+    CommunicationFail,
+    #[error("The player has run out of time.")]
+    TimedOut,
+}
 
 #[async_trait]
 pub(crate) trait Player: GameObserver {
@@ -15,9 +27,49 @@ pub(crate) trait Player: GameObserver {
         playable_cards: &Vec<PlayableCardSelector>,
         performable_basic_actions: &Vec<BasicAction>,
         available_basic_action_costs: &Vec<BasicActionCost>,
-    ) -> Result<MainPhaseAction, ()>;
+    ) -> Result<MainPhaseAction, PlayerError>;
+
+    async fn damage_target(
+        &mut self,
+        state: &StateView,
+        damage: AttackDamage,
+        available_targets: &Vec<DamageTarget>,
+    ) -> Result<DamageTarget, PlayerError>;
+
+    async fn reaction(
+        &mut self,
+        state: &StateView,
+        attack_damage: AttackDamage,
+        playable_reactions: &Vec<PlayableCardSelector>,
+    ) -> Result<ReactionAction, PlayerError>;
+
+    async fn effect_choice(&mut self, state: &StateView, options: &Vec<String>)
+        -> Result<usize, PlayerError>;
+
+    async fn cards_to_discard(
+        &mut self,
+        state: &StateView,
+        amount: usize,
+    ) -> Result<Vec<HandSelector>, PlayerError>;
+
+    /// Return the cards to put on the bottom of the deck before the game begins.
+    async fn mulligan(&mut self, state: &StateView) -> Result<Vec<HandSelector>, PlayerError>;
+
+    /// Return whether to reshuffle the played and discard piles into the deck.
+    async fn reshuffle(&mut self, state: &StateView) -> Result<bool, PlayerError>;
+
+    async fn request_game_start(&mut self, _pos: PlayerPos) -> Result<(), PlayerError> {
+        Ok(())
+    }
+
+    /// Tell the player why their response has been refused. The decision is requested again
+    /// unless they have run out of retries.
+    fn notify_rejection(&mut self, _reason: &str) -> Result<(), NotifyFailedError> {
+        Ok(())
+    }
 
-    async fn request_game_start(&mut self, _pos: PlayerPos) -> Result<(), ()> {
+    /// Tell the player how much time they have for the decision about to be requested.
+    fn notify_time_left(&mut self, _time_left: TimeLeft) -> Result<(), NotifyFailedError> {
         Ok(())
     }
 }
@@ -0,0 +1,281 @@
+use super::{Player, PlayerError};
+use crate::clock::Clock;
+use crate::game_watcher::{GameObserver, NotifyFailedError};
+use async_trait::async_trait;
+use furuyoni_lib::net::frames::{
+    GameToPlayerRequest, PlayerToGameResponse, RequestDamageTarget, RequestDiscard,
+    RequestEffectChoice, RequestMainPhaseAction, RequestReaction, TimeLeft,
+};
+use furuyoni_lib::replays::DecisionRecord;
+use furuyoni_lib::rules::attack::{AttackDamage, DamageTarget};
+use furuyoni_lib::rules::events::GameEvent;
+use furuyoni_lib::rules::player_actions::{
+    BasicAction, BasicActionCost, HandSelector, MainPhaseAction, PlayableCardSelector,
+    ReactionAction,
+};
+use furuyoni_lib::rules::states::StateView;
+use furuyoni_lib::rules::PlayerPos;
+use std::sync::{Arc, Mutex};
+use tokio::time::Instant;
+
+/// The decisions of the players of a game, shared by their `RecordingPlayer`s.
+pub(crate) struct DecisionLog {
+    decisions: Mutex<Vec<DecisionRecord>>,
+}
+
+impl DecisionLog {
+    pub fn new() -> Self {
+        Self {
+            decisions: Mutex::new(vec![]),
+        }
+    }
+
+    /// Take the decisions recorded so far.
+    pub fn take(&self) -> Vec<DecisionRecord> {
+        std::mem::take(&mut *self.decisions.lock().unwrap())
+    }
+
+    /// Add the decision and return its index.
+    fn push(&self, decision: DecisionRecord) -> usize {
+        let mut decisions = self.decisions.lock().unwrap();
+        decisions.push(decision);
+        decisions.len() - 1
+    }
+
+    fn reject(&self, index: usize, reason: &str) {
+        if let Some(decision) = self.decisions.lock().unwrap().get_mut(index) {
+            decision.rejection = Some(reason.to_string());
+        }
+    }
+}
+
+/// A player whose decisions are recorded: what the player has been offered, what it has chosen,
+/// how long it has taken, and whether the choice has been rejected.
+pub(crate) struct RecordingPlayer<C: Clock> {
+    player: Box<dyn Player + Send + Sync>,
+    pos: PlayerPos,
+    log: Arc<DecisionLog>,
+    clock: C,
+    /// The number of events the player has been notified of.
+    events_seen: usize,
+    /// The index of the player's last decision in the log, to mark it when it gets rejected.
+    last_decision: Option<usize>,
+}
+
+impl<C: Clock> RecordingPlayer<C> {
+    pub fn new(
+        player: Box<dyn Player + Send + Sync>,
+        pos: PlayerPos,
+        log: Arc<DecisionLog>,
+        clock: C,
+    ) -> Self {
+        Self {
+            player,
+            pos,
+            log,
+            clock,
+            events_seen: 0,
+            last_decision: None,
+        }
+    }
+
+    fn record(
+        &mut self,
+        request: GameToPlayerRequest,
+        started: Instant,
+        response: Option<PlayerToGameResponse>,
+    ) {
+        let decision = DecisionRecord {
+            player: self.pos,
+            event_index: self.events_seen,
+            request,
+            response,
+            latency: self.clock.now().saturating_duration_since(started),
+            rejection: None,
+        };
+        self.last_decision = Some(self.log.push(decision));
+    }
+}
+
+#[async_trait]
+impl<C: Clock + Send + Sync> Player for RecordingPlayer<C> {
+    async fn main_phase_action(
+        &mut self,
+        state: &StateView,
+        playable_cards: &Vec<PlayableCardSelector>,
+        performable_basic_actions: &Vec<BasicAction>,
+        available_basic_action_costs: &Vec<BasicActionCost>,
+    ) -> Result<MainPhaseAction, PlayerError> {
+        let started = self.clock.now();
+        let result = self
+            .player
+            .main_phase_action(
+                state,
+                playable_cards,
+                performable_basic_actions,
+                available_basic_action_costs,
+            )
+            .await;
+
+        let request = GameToPlayerRequest::RequestMainPhaseAction(RequestMainPhaseAction {
+            playable_cards: playable_cards.clone(),
+            performable_basic_actions: performable_basic_actions.clone(),
+            available_basic_action_costs: available_basic_action_costs.clone(),
+        });
+        let response = result
+            .as_ref()
+            .ok()
+            .cloned()
+            .map(PlayerToGameResponse::MainPhaseAction);
+        self.record(request, started, response);
+
+        result
+    }
+
+    async fn damage_target(
+        &mut self,
+        state: &StateView,
+        damage: AttackDamage,
+        available_targets: &Vec<DamageTarget>,
+    ) -> Result<DamageTarget, PlayerError> {
+        let started = self.clock.now();
+        let result = self
+            .player
+            .damage_target(state, damage, available_targets)
+            .await;
+
+        let request = GameToPlayerRequest::RequestDamageTarget(RequestDamageTarget {
+            damage,
+            available_targets: available_targets.clone(),
+        });
+        let response = result
+            .as_ref()
+            .ok()
+            .cloned()
+            .map(PlayerToGameResponse::DamageTarget);
+        self.record(request, started, response);
+
+        result
+    }
+
+    async fn reaction(
+        &mut self,
+        state: &StateView,
+        attack_damage: AttackDamage,
+        playable_reactions: &Vec<PlayableCardSelector>,
+    ) -> Result<ReactionAction, PlayerError> {
+        let started = self.clock.now();
+        let result = self
+            .player
+            .reaction(state, attack_damage, playable_reactions)
+            .await;
+
+        let request = GameToPlayerRequest::RequestReaction(RequestReaction {
+            attack_damage,
+            playable_reactions: playable_reactions.clone(),
+        });
+        let response = result
+            .as_ref()
+            .ok()
+            .cloned()
+            .map(PlayerToGameResponse::Reaction);
+        self.record(request, started, response);
+
+        result
+    }
+
+    async fn effect_choice(
+        &mut self,
+        state: &StateView,
+        options: &Vec<String>,
+    ) -> Result<usize, PlayerError> {
+        let started = self.clock.now();
+        let result = self.player.effect_choice(state, options).await;
+
+        let request = GameToPlayerRequest::RequestEffectChoice(RequestEffectChoice {
+            options: options.clone(),
+        });
+        let response = result
+            .as_ref()
+            .ok()
+            .cloned()
+            .map(PlayerToGameResponse::EffectChoice);
+        self.record(request, started, response);
+
+        result
+    }
+
+    async fn cards_to_discard(
+        &mut self,
+        state: &StateView,
+        amount: usize,
+    ) -> Result<Vec<HandSelector>, PlayerError> {
+        let started = self.clock.now();
+        let result = self.player.cards_to_discard(state, amount).await;
+
+        let request = GameToPlayerRequest::RequestDiscard(RequestDiscard { amount });
+        let response = result
+            .as_ref()
+            .ok()
+            .cloned()
+            .map(PlayerToGameResponse::Discard);
+        self.record(request, started, response);
+
+        result
+    }
+
+    async fn mulligan(&mut self, state: &StateView) -> Result<Vec<HandSelector>, PlayerError> {
+        let started = self.clock.now();
+        let result = self.player.mulligan(state).await;
+
+        let response = result
+            .as_ref()
+            .ok()
+            .cloned()
+            .map(PlayerToGameResponse::Mulligan);
+        self.record(GameToPlayerRequest::RequestMulligan, started, response);
+
+        result
+    }
+
+    async fn reshuffle(&mut self, state: &StateView) -> Result<bool, PlayerError> {
+        let started = self.clock.now();
+        let result = self.player.reshuffle(state).await;
+
+        let response = result
+            .as_ref()
+            .ok()
+            .cloned()
+            .map(PlayerToGameResponse::Reshuffle);
+        self.record(GameToPlayerRequest::RequestReshuffle, started, response);
+
+        result
+    }
+
+    async fn request_game_start(&mut self, pos: PlayerPos) -> Result<(), PlayerError> {
+        self.player.request_game_start(pos).await
+    }
+
+    fn notify_rejection(&mut self, reason: &str) -> Result<(), NotifyFailedError> {
+        if let Some(index) = self.last_decision {
+            self.log.reject(index, reason);
+        }
+
+        self.player.notify_rejection(reason)
+    }
+
+    fn notify_time_left(&mut self, time_left: TimeLeft) -> Result<(), NotifyFailedError> {
+        self.player.notify_time_left(time_left)
+    }
+}
+
+impl<C: Clock> GameObserver for RecordingPlayer<C> {
+    fn initialize_state(&mut self, state: &StateView) -> Result<(), NotifyFailedError> {
+        self.player.initialize_state(state)
+    }
+
+    fn notify_event(&mut self, event: GameEvent) -> Result<(), NotifyFailedError> {
+        self.events_seen += 1;
+        self.player.notify_event(event)
+    }
+}
@@ -1,37 +1,183 @@
 use async_trait::async_trait;
 use furuyoni_lib::net::frames::{
-    GameToPlayerRequest, PlayerToGameResponse, RequestMainPhaseAction,
+    GameToPlayerRequest, PlayerToGameResponse, RequestDamageTarget, RequestDiscard,
+    RequestEffectChoice, RequestMainPhaseAction, RequestReaction, TimeLeft,
 };
 use furuyoni_lib::net::message_channel::MessageChannel;
 use furuyoni_lib::rules::events::GameEvent;
 
 use crate::game_watcher::{GameObserver, NotifyFailedError};
-use crate::players::Player;
+use crate::players::{Player, PlayerError};
+use furuyoni_lib::rules::attack::{AttackDamage, DamageTarget};
 use furuyoni_lib::rules::player_actions::{
-    BasicAction, BasicActionCost, MainPhaseAction, PlayableCardSelector,
+    BasicAction, BasicActionCost, HandSelector, MainPhaseAction, PlayableCardSelector,
+    ReactionAction,
 };
 use furuyoni_lib::rules::states::StateView;
 use furuyoni_lib::rules::PlayerPos;
+use std::mem;
+use tokio::sync::{mpsc, oneshot};
 
 type ChannelT = MessageChannel<GameToPlayerRequest, PlayerToGameResponse>;
 
 pub(crate) struct RemotePlayer {
     channel: ChannelT,
+    /// The channels of the player reconnecting to the game. Closed once the player can't come back.
+    reconnect_rx: mpsc::Receiver<ChannelT>,
+    pos: Option<PlayerPos>,
+    /// The state as the player sees it, sent again when the player reconnects.
+    state: Option<StateView>,
+    /// The reconnected client has been asked to start the game and is yet to acknowledge.
+    awaiting_ack: bool,
+    /// The number of requests sent to the client whose responses haven't been received yet. A
+    /// request stays unanswered when the game stops waiting for it, like when the player runs out
+    /// of time. The client still answers it before the next request, so that response is dropped.
+    unanswered_requests: usize,
+    /// Gives the channel back once the player is dropped, for the client to play another game.
+    returned_tx: Option<oneshot::Sender<ChannelT>>,
 }
 
 impl RemotePlayer {
-    pub fn new(channel: ChannelT) -> Self {
-        Self { channel }
+    pub fn new(
+        channel: ChannelT,
+        reconnect_rx: mpsc::Receiver<ChannelT>,
+        returned_tx: oneshot::Sender<ChannelT>,
+    ) -> Self {
+        Self {
+            channel,
+            reconnect_rx,
+            pos: None,
+            state: None,
+            awaiting_ack: false,
+            unanswered_requests: 0,
+            returned_tx: Some(returned_tx),
+        }
+    }
+}
+
+impl Drop for RemotePlayer {
+    fn drop(&mut self) {
+        // The player may have reconnected since the last request.
+        while let Ok(channel) = self.reconnect_rx.try_recv() {
+            self.channel = channel;
+        }
+
+        if let Some(returned_tx) = self.returned_tx.take() {
+            let closed = MessageChannel::new(mpsc::channel(1).0, mpsc::channel(1).1);
+
+            // The client may have left.
+            let _ = returned_tx.send(mem::replace(&mut self.channel, closed));
+        }
     }
 }
 
 impl RemotePlayer {
-    fn send_state(&mut self, state: &StateView) -> Result<(), ()> {
-        self.channel
-            .send(GameToPlayerRequest::CheckGameState(state.clone()))
-            .map_err(|_| ())?;
+    fn send_state(&mut self, state: &StateView) {
+        self.state = Some(state.clone());
+
+        // A disconnection is handled by the request that follows.
+        let _ = self
+            .channel
+            .send(GameToPlayerRequest::CheckGameState(state.clone()));
+    }
+
+    /// Send a notification. Failing to send is fine as long as the player may reconnect, since
+    /// the player gets the whole state again on reconnection.
+    fn notify(&mut self, notification: GameToPlayerRequest) -> Result<(), NotifyFailedError> {
+        self.poll_reconnection();
+
+        if self.channel.send(notification).is_err() && self.reconnect_rx.is_closed() {
+            return Err(NotifyFailedError);
+        }
         Ok(())
     }
+
+    /// Send the request and wait for its response. If the player has been disconnected, wait for
+    /// the player to reconnect, then request again.
+    async fn request(
+        &mut self,
+        request: GameToPlayerRequest,
+    ) -> Result<PlayerToGameResponse, PlayerError> {
+        loop {
+            self.poll_reconnection();
+
+            if let Ok(response) = self.try_request(request.clone()).await {
+                return Ok(response);
+            }
+
+            let channel = self
+                .reconnect_rx
+                .recv()
+                .await
+                .ok_or(PlayerError::CommunicationFail)?;
+            self.attach(channel);
+        }
+    }
+
+    async fn try_request(
+        &mut self,
+        request: GameToPlayerRequest,
+    ) -> Result<PlayerToGameResponse, PlayerError> {
+        if self.awaiting_ack {
+            let response = self
+                .channel
+                .receive()
+                .await
+                .map_err(|_| PlayerError::CommunicationFail)?;
+            if !matches!(response, PlayerToGameResponse::AcknowledgeGameStart) {
+                return Err(PlayerError::CommunicationFail);
+            }
+            self.awaiting_ack = false;
+        }
+
+        self.channel
+            .send(request)
+            .map_err(|_| PlayerError::CommunicationFail)?;
+        self.unanswered_requests += 1;
+
+        // The responses come in the order of the requests.
+        loop {
+            let response = self
+                .channel
+                .receive()
+                .await
+                .map_err(|_| PlayerError::CommunicationFail)?;
+            self.unanswered_requests -= 1;
+
+            if self.unanswered_requests == 0 {
+                return Ok(response);
+            }
+        }
+    }
+
+    /// Switch to the channel of the player if the player has reconnected.
+    fn poll_reconnection(&mut self) {
+        while let Ok(channel) = self.reconnect_rx.try_recv() {
+            self.attach(channel);
+        }
+    }
+
+    /// Initialize the new client the same way as at the start of the game. The acknowledgement
+    /// of the game start is awaited before the next request.
+    fn attach(&mut self, channel: ChannelT) {
+        self.channel = channel;
+        self.awaiting_ack = false;
+        // The new client hasn't been sent the requests of the old one.
+        self.unanswered_requests = 0;
+
+        // A failure is handled by the next request.
+        if let Some(state) = &self.state {
+            let _ = self
+                .channel
+                .send(GameToPlayerRequest::InitializeGameState(state.clone()));
+        }
+        if let Some(pos) = self.pos {
+            let _ = self
+                .channel
+                .send(GameToPlayerRequest::RequestGameStart { pos });
+            self.awaiting_ack = true;
+        }
+    }
 }
 #[async_trait]
 impl Player for RemotePlayer {
@@ -41,54 +187,245 @@ impl Player for RemotePlayer {
         playable_cards: &Vec<PlayableCardSelector>,
         performable_basic_actions: &Vec<BasicAction>,
         available_basic_action_costs: &Vec<BasicActionCost>,
-    ) -> Result<MainPhaseAction, ()> {
-        self.send_state(state)?;
+    ) -> Result<MainPhaseAction, PlayerError> {
+        self.send_state(state);
 
-        self.channel
-            .send(GameToPlayerRequest::RequestMainPhaseAction(
+        let response = self
+            .request(GameToPlayerRequest::RequestMainPhaseAction(
                 RequestMainPhaseAction {
                     playable_cards: playable_cards.clone(),
                     performable_basic_actions: performable_basic_actions.clone(),
                     available_basic_action_costs: available_basic_action_costs.clone(),
                 },
             ))
-            .map_err(|_| ())?;
-
-        let response = self.channel.receive().await.map_err(|_| ())?;
+            .await?;
 
         if let PlayerToGameResponse::MainPhaseAction(response) = response {
             Ok(response)
         } else {
-            Err(())
+            Err(PlayerError::CommunicationFail)
         }
     }
 
-    async fn request_game_start(&mut self, pos: PlayerPos) -> Result<(), ()> {
-        self.channel
-            .send(GameToPlayerRequest::RequestGameStart { pos })
-            .map_err(|_| ())?;
+    async fn damage_target(
+        &mut self,
+        state: &StateView,
+        damage: AttackDamage,
+        available_targets: &Vec<DamageTarget>,
+    ) -> Result<DamageTarget, PlayerError> {
+        self.send_state(state);
+
+        let response = self
+            .request(GameToPlayerRequest::RequestDamageTarget(
+                RequestDamageTarget {
+                    damage,
+                    available_targets: available_targets.clone(),
+                },
+            ))
+            .await?;
+
+        if let PlayerToGameResponse::DamageTarget(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn reaction(
+        &mut self,
+        state: &StateView,
+        attack_damage: AttackDamage,
+        playable_reactions: &Vec<PlayableCardSelector>,
+    ) -> Result<ReactionAction, PlayerError> {
+        self.send_state(state);
+
+        let response = self
+            .request(GameToPlayerRequest::RequestReaction(RequestReaction {
+                attack_damage,
+                playable_reactions: playable_reactions.clone(),
+            }))
+            .await?;
+
+        if let PlayerToGameResponse::Reaction(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn effect_choice(
+        &mut self,
+        state: &StateView,
+        options: &Vec<String>,
+    ) -> Result<usize, PlayerError> {
+        self.send_state(state);
+
+        let response = self
+            .request(GameToPlayerRequest::RequestEffectChoice(
+                RequestEffectChoice {
+                    options: options.clone(),
+                },
+            ))
+            .await?;
+
+        if let PlayerToGameResponse::EffectChoice(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn cards_to_discard(
+        &mut self,
+        state: &StateView,
+        amount: usize,
+    ) -> Result<Vec<HandSelector>, PlayerError> {
+        self.send_state(state);
+
+        let response = self
+            .request(GameToPlayerRequest::RequestDiscard(RequestDiscard { amount }))
+            .await?;
+
+        if let PlayerToGameResponse::Discard(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn mulligan(&mut self, state: &StateView) -> Result<Vec<HandSelector>, PlayerError> {
+        self.send_state(state);
+
+        let response = self.request(GameToPlayerRequest::RequestMulligan).await?;
+
+        if let PlayerToGameResponse::Mulligan(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
 
-        let response = self.channel.receive().await.map_err(|_| ())?;
+    async fn reshuffle(&mut self, state: &StateView) -> Result<bool, PlayerError> {
+        self.send_state(state);
+
+        let response = self.request(GameToPlayerRequest::RequestReshuffle).await?;
+
+        if let PlayerToGameResponse::Reshuffle(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    fn notify_rejection(&mut self, reason: &str) -> Result<(), NotifyFailedError> {
+        self.notify(GameToPlayerRequest::NotifyRejection(reason.to_string()))
+    }
+
+    fn notify_time_left(&mut self, time_left: TimeLeft) -> Result<(), NotifyFailedError> {
+        self.notify(GameToPlayerRequest::NotifyTimeLeft(time_left))
+    }
+
+    async fn request_game_start(&mut self, pos: PlayerPos) -> Result<(), PlayerError> {
+        let response = self
+            .request(GameToPlayerRequest::RequestGameStart { pos })
+            .await?;
 
         if let PlayerToGameResponse::AcknowledgeGameStart = response {
+            self.pos = Some(pos);
             Ok(())
         } else {
-            Err(())
+            Err(PlayerError::CommunicationFail)
         }
     }
 }
 impl GameObserver for RemotePlayer {
-    fn initialize_state(&mut self, _state: &StateView) -> Result<(), NotifyFailedError> {
-        self.channel
-            .send(GameToPlayerRequest::InitializeGameState(_state.clone()))
-            .map_err(|_| NotifyFailedError)?;
+    fn initialize_state(&mut self, state: &StateView) -> Result<(), NotifyFailedError> {
+        self.notify(GameToPlayerRequest::InitializeGameState(state.clone()))?;
+        self.state = Some(state.clone());
+
         Ok(())
     }
 
     fn notify_event(&mut self, event: GameEvent) -> Result<(), NotifyFailedError> {
-        self.channel
-            .send(GameToPlayerRequest::NotifyEvent(event))
-            .map_err(|_| NotifyFailedError)?;
+        // Notify first, so that a reconnected client gets the state before the update.
+        self.notify(GameToPlayerRequest::NotifyEvent(event))?;
+
+        if let GameEvent::StateUpdated(update) = event
+            && let Some(state) = &mut self.state
+        {
+            // The state is refreshed on the next decision anyway.
+            let _ = state.apply_update(update);
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[tokio::test]
+    async fn late_response_is_not_taken_for_the_next_request() {
+        let (request_tx, _request_rx) = mpsc::channel(8);
+        let (response_tx, response_rx) = mpsc::channel(8);
+        let (_reconnect_tx, reconnect_rx) = mpsc::channel(1);
+        let mut player = RemotePlayer::new(
+            MessageChannel::new(request_tx, response_rx),
+            reconnect_rx,
+            oneshot::channel().0,
+        );
+
+        // The game stops waiting for the first response, like when the player runs out of time.
+        let abandoned = player
+            .request(GameToPlayerRequest::RequestReshuffle)
+            .now_or_never();
+        assert!(abandoned.is_none());
+
+        response_tx
+            .send(PlayerToGameResponse::Reshuffle(true))
+            .await
+            .unwrap();
+        response_tx
+            .send(PlayerToGameResponse::Reshuffle(false))
+            .await
+            .unwrap();
+
+        let response = player.request(GameToPlayerRequest::RequestReshuffle).await;
+        assert!(matches!(
+            response,
+            Ok(PlayerToGameResponse::Reshuffle(false))
+        ));
+    }
+
+    #[tokio::test]
+    async fn dropped_player_returns_the_channel_of_the_reconnected_client() {
+        let (old_request_tx, _old_request_rx) = mpsc::channel(8);
+        let (_old_response_tx, old_response_rx) = mpsc::channel(8);
+        let (new_request_tx, mut new_request_rx) = mpsc::channel(8);
+        let (_new_response_tx, new_response_rx) = mpsc::channel(8);
+        let (reconnect_tx, reconnect_rx) = mpsc::channel(1);
+        let (returned_tx, returned_rx) = oneshot::channel();
+        let player = RemotePlayer::new(
+            MessageChannel::new(old_request_tx, old_response_rx),
+            reconnect_rx,
+            returned_tx,
+        );
+
+        reconnect_tx
+            .send(MessageChannel::new(new_request_tx, new_response_rx))
+            .await
+            .unwrap();
+        drop(player);
+
+        let returned = returned_rx.await.unwrap();
+        returned
+            .send(GameToPlayerRequest::RequestReshuffle)
+            .unwrap();
+        assert!(matches!(
+            new_request_rx.recv().await,
+            Some(GameToPlayerRequest::RequestReshuffle)
+        ));
+    }
+}
@@ -0,0 +1,249 @@
+use super::{Player, PlayerError};
+use crate::game_watcher::{GameObserver, NotifyFailedError};
+use async_trait::async_trait;
+use furuyoni_lib::net::frames::{
+    GameToPlayerRequest, PlayerToGameResponse, RequestDamageTarget, RequestDiscard,
+    RequestEffectChoice, RequestMainPhaseAction, RequestReaction,
+};
+use furuyoni_lib::replays::DecisionRecord;
+use furuyoni_lib::rules::attack::{AttackDamage, DamageTarget};
+use furuyoni_lib::rules::player_actions::{
+    BasicAction, BasicActionCost, HandSelector, MainPhaseAction, PlayableCardSelector,
+    ReactionAction,
+};
+use furuyoni_lib::rules::states::StateView;
+use furuyoni_lib::rules::{GameEndReason, GameOutcome, GameResult, PlayerPos};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// A request which has differed from the one the player has been recorded with.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestMismatch {
+    pub player: PlayerPos,
+    /// The number of events that had happened when the recorded request was made.
+    pub event_index: usize,
+    pub recorded: GameToPlayerRequest,
+    pub resimulated: GameToPlayerRequest,
+}
+
+/// A player that makes the recorded decisions of a player again, in the same order.
+pub(crate) struct ScriptedPlayer {
+    pos: PlayerPos,
+    decisions: VecDeque<DecisionRecord>,
+    /// How the recorded game has ended, to end the game the same way once the script runs out.
+    recorded_outcome: Option<GameOutcome>,
+    surrender_tx: mpsc::Sender<PlayerPos>,
+    /// The first request that has differed from the recorded one, of either player.
+    mismatch: Arc<Mutex<Option<RequestMismatch>>>,
+}
+
+impl ScriptedPlayer {
+    pub fn new(
+        pos: PlayerPos,
+        decisions: &[DecisionRecord],
+        recorded_outcome: Option<GameOutcome>,
+        surrender_tx: mpsc::Sender<PlayerPos>,
+        mismatch: Arc<Mutex<Option<RequestMismatch>>>,
+    ) -> Self {
+        let decisions = decisions
+            .iter()
+            .filter(|decision| decision.player == pos)
+            .cloned()
+            .collect();
+
+        Self {
+            pos,
+            decisions,
+            recorded_outcome,
+            surrender_tx,
+            mismatch,
+        }
+    }
+
+    /// Return the recorded response to the next decision.
+    async fn respond(
+        &mut self,
+        request: GameToPlayerRequest,
+    ) -> Result<PlayerToGameResponse, PlayerError> {
+        let Some(decision) = self.decisions.pop_front() else {
+            return self.end_script().await;
+        };
+
+        if decision.request != request {
+            self.mismatch
+                .lock()
+                .unwrap()
+                .get_or_insert(RequestMismatch {
+                    player: self.pos,
+                    event_index: decision.event_index,
+                    recorded: decision.request,
+                    resimulated: request,
+                });
+        }
+
+        decision.response.ok_or_else(|| self.failure())
+    }
+
+    /// End the game the way the recorded game has ended, since the recorded game has ended while
+    /// waiting for this decision.
+    async fn end_script(&mut self) -> Result<PlayerToGameResponse, PlayerError> {
+        if let Some(GameOutcome {
+            result: GameResult::Winner(winner),
+            reason: GameEndReason::Surrender,
+            ..
+        }) = self.recorded_outcome
+        {
+            // Either player may have surrendered while the game was waiting for this player.
+            let _ = self.surrender_tx.send(winner.other()).await;
+            return std::future::pending().await;
+        }
+
+        Err(self.failure())
+    }
+
+    /// The error the recorded player has failed with.
+    fn failure(&self) -> PlayerError {
+        match self.recorded_outcome {
+            Some(GameOutcome {
+                reason: GameEndReason::Timeout,
+                ..
+            }) => PlayerError::TimedOut,
+            _ => PlayerError::CommunicationFail,
+        }
+    }
+}
+
+#[async_trait]
+impl Player for ScriptedPlayer {
+    async fn main_phase_action(
+        &mut self,
+        _state: &StateView,
+        playable_cards: &Vec<PlayableCardSelector>,
+        performable_basic_actions: &Vec<BasicAction>,
+        available_basic_action_costs: &Vec<BasicActionCost>,
+    ) -> Result<MainPhaseAction, PlayerError> {
+        let response = self
+            .respond(GameToPlayerRequest::RequestMainPhaseAction(
+                RequestMainPhaseAction {
+                    playable_cards: playable_cards.clone(),
+                    performable_basic_actions: performable_basic_actions.clone(),
+                    available_basic_action_costs: available_basic_action_costs.clone(),
+                },
+            ))
+            .await?;
+
+        if let PlayerToGameResponse::MainPhaseAction(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn damage_target(
+        &mut self,
+        _state: &StateView,
+        damage: AttackDamage,
+        available_targets: &Vec<DamageTarget>,
+    ) -> Result<DamageTarget, PlayerError> {
+        let response = self
+            .respond(GameToPlayerRequest::RequestDamageTarget(
+                RequestDamageTarget {
+                    damage,
+                    available_targets: available_targets.clone(),
+                },
+            ))
+            .await?;
+
+        if let PlayerToGameResponse::DamageTarget(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn reaction(
+        &mut self,
+        _state: &StateView,
+        attack_damage: AttackDamage,
+        playable_reactions: &Vec<PlayableCardSelector>,
+    ) -> Result<ReactionAction, PlayerError> {
+        let response = self
+            .respond(GameToPlayerRequest::RequestReaction(RequestReaction {
+                attack_damage,
+                playable_reactions: playable_reactions.clone(),
+            }))
+            .await?;
+
+        if let PlayerToGameResponse::Reaction(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn effect_choice(
+        &mut self,
+        _state: &StateView,
+        options: &Vec<String>,
+    ) -> Result<usize, PlayerError> {
+        let response = self
+            .respond(GameToPlayerRequest::RequestEffectChoice(
+                RequestEffectChoice {
+                    options: options.clone(),
+                },
+            ))
+            .await?;
+
+        if let PlayerToGameResponse::EffectChoice(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn cards_to_discard(
+        &mut self,
+        _state: &StateView,
+        amount: usize,
+    ) -> Result<Vec<HandSelector>, PlayerError> {
+        let response = self
+            .respond(GameToPlayerRequest::RequestDiscard(RequestDiscard {
+                amount,
+            }))
+            .await?;
+
+        if let PlayerToGameResponse::Discard(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn mulligan(&mut self, _state: &StateView) -> Result<Vec<HandSelector>, PlayerError> {
+        let response = self.respond(GameToPlayerRequest::RequestMulligan).await?;
+
+        if let PlayerToGameResponse::Mulligan(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    async fn reshuffle(&mut self, _state: &StateView) -> Result<bool, PlayerError> {
+        let response = self.respond(GameToPlayerRequest::RequestReshuffle).await?;
+
+        if let PlayerToGameResponse::Reshuffle(response) = response {
+            Ok(response)
+        } else {
+            Err(PlayerError::CommunicationFail)
+        }
+    }
+
+    fn notify_rejection(&mut self, _reason: &str) -> Result<(), NotifyFailedError> {
+        // The next recorded decision is the one made after the rejection.
+        Ok(())
+    }
+}
+
+impl GameObserver for ScriptedPlayer {}
@@ -0,0 +1,468 @@
+use super::{IdlePlayer, Player, PlayerError};
+use crate::clock::Clock;
+use crate::game_watcher::{GameObserver, NotifyFailedError};
+use async_trait::async_trait;
+use furuyoni_lib::net::frames::TimeLeft;
+use furuyoni_lib::rules::attack::{AttackDamage, DamageTarget};
+use furuyoni_lib::rules::events::GameEvent;
+use furuyoni_lib::rules::player_actions::{
+    BasicAction, BasicActionCost, HandSelector, MainPhaseAction, PlayableCardSelector,
+    ReactionAction,
+};
+use furuyoni_lib::rules::states::StateView;
+use furuyoni_lib::rules::PlayerPos;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum TimeoutPolicy {
+    /// Take the default action of an idle player for the decision.
+    DefaultAction,
+    /// Lose the game.
+    Forfeit,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct TimeControl {
+    /// The time given to every decision. `None` for no limit.
+    pub per_decision: Option<Duration>,
+    /// The time for the whole game, spent when a decision takes longer than `per_decision`.
+    /// `None` for no bank.
+    pub time_bank: Option<Duration>,
+    pub on_timeout: TimeoutPolicy,
+}
+
+/// A player whose decisions are limited in time.
+pub(crate) struct TimedPlayer<C: Clock> {
+    player: Box<dyn Player + Send + Sync>,
+    fallback: IdlePlayer,
+    clock: C,
+    per_decision: Option<Duration>,
+    time_bank: Option<Duration>,
+    on_timeout: TimeoutPolicy,
+}
+
+impl<C: Clock> TimedPlayer<C> {
+    pub fn new(player: Box<dyn Player + Send + Sync>, time_control: TimeControl, clock: C) -> Self {
+        Self {
+            player,
+            fallback: IdlePlayer {},
+            clock,
+            per_decision: time_control.per_decision,
+            time_bank: time_control.time_bank,
+            on_timeout: time_control.on_timeout,
+        }
+    }
+
+    fn time_left(&self) -> TimeLeft {
+        TimeLeft {
+            decision: self.per_decision,
+            bank: self.time_bank,
+        }
+    }
+
+    fn limit(&self) -> Option<Duration> {
+        match (self.per_decision, self.time_bank) {
+            (None, None) => None,
+            (decision, bank) => Some(decision.unwrap_or_default() + bank.unwrap_or_default()),
+        }
+    }
+
+    /// Tell the player their time, then start counting.
+    fn start_decision(&mut self) -> Result<Instant, PlayerError> {
+        self.player
+            .notify_time_left(self.time_left())
+            .map_err(|_| PlayerError::CommunicationFail)?;
+
+        Ok(self.clock.now())
+    }
+
+    /// Charge the time spent over the decision's time to the bank. Returns `None` if the player
+    /// has timed out and the default action should be taken instead.
+    fn finish_decision<T>(
+        &mut self,
+        started: Instant,
+        response: Option<Result<T, PlayerError>>,
+    ) -> Result<Option<T>, PlayerError> {
+        match response {
+            Some(response) => {
+                let elapsed = self.clock.now().saturating_duration_since(started);
+                let overtime = elapsed.saturating_sub(self.per_decision.unwrap_or_default());
+                if let Some(bank) = &mut self.time_bank {
+                    *bank = bank.saturating_sub(overtime);
+                }
+
+                response.map(Some)
+            }
+            None => {
+                if let Some(bank) = &mut self.time_bank {
+                    *bank = Duration::ZERO;
+                }
+
+                match self.on_timeout {
+                    TimeoutPolicy::DefaultAction => Ok(None),
+                    TimeoutPolicy::Forfeit => Err(PlayerError::TimedOut),
+                }
+            }
+        }
+    }
+}
+
+/// Wait for the decision until the limit. Returns `None` if the time is up. The player should
+/// ignore a response made too late, like `RemotePlayer` does.
+async fn within<T>(
+    clock: &impl Clock,
+    limit: Option<Duration>,
+    decision: impl Future<Output = T>,
+) -> Option<T> {
+    match limit {
+        None => Some(decision.await),
+        Some(limit) => tokio::select! {
+            response = decision => Some(response),
+            _ = clock.sleep(limit) => None,
+        },
+    }
+}
+
+#[async_trait]
+impl<C: Clock + Send + Sync> Player for TimedPlayer<C> {
+    async fn main_phase_action(
+        &mut self,
+        state: &StateView,
+        playable_cards: &Vec<PlayableCardSelector>,
+        performable_basic_actions: &Vec<BasicAction>,
+        available_basic_action_costs: &Vec<BasicActionCost>,
+    ) -> Result<MainPhaseAction, PlayerError> {
+        let started = self.start_decision()?;
+        let response = within(
+            &self.clock,
+            self.limit(),
+            self.player.main_phase_action(
+                state,
+                playable_cards,
+                performable_basic_actions,
+                available_basic_action_costs,
+            ),
+        )
+        .await;
+
+        match self.finish_decision(started, response)? {
+            Some(response) => Ok(response),
+            None => {
+                self.fallback
+                    .main_phase_action(
+                        state,
+                        playable_cards,
+                        performable_basic_actions,
+                        available_basic_action_costs,
+                    )
+                    .await
+            }
+        }
+    }
+
+    async fn damage_target(
+        &mut self,
+        state: &StateView,
+        damage: AttackDamage,
+        available_targets: &Vec<DamageTarget>,
+    ) -> Result<DamageTarget, PlayerError> {
+        let started = self.start_decision()?;
+        let response = within(
+            &self.clock,
+            self.limit(),
+            self.player.damage_target(state, damage, available_targets),
+        )
+        .await;
+
+        match self.finish_decision(started, response)? {
+            Some(response) => Ok(response),
+            None => {
+                self.fallback
+                    .damage_target(state, damage, available_targets)
+                    .await
+            }
+        }
+    }
+
+    async fn reaction(
+        &mut self,
+        state: &StateView,
+        attack_damage: AttackDamage,
+        playable_reactions: &Vec<PlayableCardSelector>,
+    ) -> Result<ReactionAction, PlayerError> {
+        let started = self.start_decision()?;
+        let response = within(
+            &self.clock,
+            self.limit(),
+            self.player
+                .reaction(state, attack_damage, playable_reactions),
+        )
+        .await;
+
+        match self.finish_decision(started, response)? {
+            Some(response) => Ok(response),
+            None => {
+                self.fallback
+                    .reaction(state, attack_damage, playable_reactions)
+                    .await
+            }
+        }
+    }
+
+    async fn effect_choice(
+        &mut self,
+        state: &StateView,
+        options: &Vec<String>,
+    ) -> Result<usize, PlayerError> {
+        let started = self.start_decision()?;
+        let response = within(
+            &self.clock,
+            self.limit(),
+            self.player.effect_choice(state, options),
+        )
+        .await;
+
+        match self.finish_decision(started, response)? {
+            Some(response) => Ok(response),
+            None => self.fallback.effect_choice(state, options).await,
+        }
+    }
+
+    async fn cards_to_discard(
+        &mut self,
+        state: &StateView,
+        amount: usize,
+    ) -> Result<Vec<HandSelector>, PlayerError> {
+        let started = self.start_decision()?;
+        let response = within(
+            &self.clock,
+            self.limit(),
+            self.player.cards_to_discard(state, amount),
+        )
+        .await;
+
+        match self.finish_decision(started, response)? {
+            Some(response) => Ok(response),
+            None => self.fallback.cards_to_discard(state, amount).await,
+        }
+    }
+
+    async fn mulligan(&mut self, state: &StateView) -> Result<Vec<HandSelector>, PlayerError> {
+        let started = self.start_decision()?;
+        let response = within(&self.clock, self.limit(), self.player.mulligan(state)).await;
+
+        match self.finish_decision(started, response)? {
+            Some(response) => Ok(response),
+            None => self.fallback.mulligan(state).await,
+        }
+    }
+
+    async fn reshuffle(&mut self, state: &StateView) -> Result<bool, PlayerError> {
+        let started = self.start_decision()?;
+        let response = within(&self.clock, self.limit(), self.player.reshuffle(state)).await;
+
+        match self.finish_decision(started, response)? {
+            Some(response) => Ok(response),
+            None => self.fallback.reshuffle(state).await,
+        }
+    }
+
+    async fn request_game_start(&mut self, pos: PlayerPos) -> Result<(), PlayerError> {
+        self.player.request_game_start(pos).await
+    }
+
+    fn notify_rejection(&mut self, reason: &str) -> Result<(), NotifyFailedError> {
+        self.player.notify_rejection(reason)
+    }
+
+    fn notify_time_left(&mut self, time_left: TimeLeft) -> Result<(), NotifyFailedError> {
+        self.player.notify_time_left(time_left)
+    }
+}
+
+impl<C: Clock> GameObserver for TimedPlayer<C> {
+    fn initialize_state(&mut self, state: &StateView) -> Result<(), NotifyFailedError> {
+        self.player.initialize_state(state)
+    }
+
+    fn notify_event(&mut self, event: GameEvent) -> Result<(), NotifyFailedError> {
+        self.player.notify_event(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::game::default_deck_list;
+    use furuyoni_lib::rules::states::{
+        filter_state, GameState, Petals, Phase, PlayerState, PlayerStates,
+    };
+    use furuyoni_lib::rules::ObservePosition;
+
+    /// A player who takes the given time to play a main phase action, and plays every other
+    /// decision like an idle player.
+    struct SlowPlayer {
+        clock: ManualClock,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Player for SlowPlayer {
+        async fn main_phase_action(
+            &mut self,
+            _state: &StateView,
+            _playable_cards: &Vec<PlayableCardSelector>,
+            _performable_basic_actions: &Vec<BasicAction>,
+            _available_basic_action_costs: &Vec<BasicActionCost>,
+        ) -> Result<MainPhaseAction, PlayerError> {
+            self.clock.sleep(self.delay).await;
+            Ok(MainPhaseAction::PlayBasicAction {
+                action: BasicAction::Focus,
+                cost: BasicActionCost::Vigor,
+            })
+        }
+
+        async fn damage_target(
+            &mut self,
+            state: &StateView,
+            damage: AttackDamage,
+            available_targets: &Vec<DamageTarget>,
+        ) -> Result<DamageTarget, PlayerError> {
+            IdlePlayer {}
+                .damage_target(state, damage, available_targets)
+                .await
+        }
+
+        async fn reaction(
+            &mut self,
+            state: &StateView,
+            attack_damage: AttackDamage,
+            playable_reactions: &Vec<PlayableCardSelector>,
+        ) -> Result<ReactionAction, PlayerError> {
+            IdlePlayer {}
+                .reaction(state, attack_damage, playable_reactions)
+                .await
+        }
+
+        async fn effect_choice(
+            &mut self,
+            state: &StateView,
+            options: &Vec<String>,
+        ) -> Result<usize, PlayerError> {
+            IdlePlayer {}.effect_choice(state, options).await
+        }
+
+        async fn cards_to_discard(
+            &mut self,
+            state: &StateView,
+            amount: usize,
+        ) -> Result<Vec<HandSelector>, PlayerError> {
+            IdlePlayer {}.cards_to_discard(state, amount).await
+        }
+
+        async fn mulligan(&mut self, state: &StateView) -> Result<Vec<HandSelector>, PlayerError> {
+            IdlePlayer {}.mulligan(state).await
+        }
+
+        async fn reshuffle(&mut self, state: &StateView) -> Result<bool, PlayerError> {
+            IdlePlayer {}.reshuffle(state).await
+        }
+    }
+
+    impl GameObserver for SlowPlayer {}
+
+    const TIME_CONTROL: TimeControl = TimeControl {
+        per_decision: Some(Duration::from_secs(10)),
+        time_bank: Some(Duration::from_secs(5)),
+        on_timeout: TimeoutPolicy::DefaultAction,
+    };
+
+    fn state() -> StateView {
+        let state = GameState::new(
+            1,
+            PlayerPos::P1,
+            Phase::Main,
+            Petals::new(10, Some(10)),
+            Petals::new(0, None),
+            PlayerStates::new(
+                PlayerState::from_deck_list(&default_deck_list()),
+                PlayerState::from_deck_list(&default_deck_list()),
+            ),
+        );
+        filter_state(ObservePosition::RelativeTo(PlayerPos::P1), &state)
+    }
+
+    /// Let the player decide while the time passes, and return the decision.
+    async fn decide_while(
+        player: &mut TimedPlayer<ManualClock>,
+        clock: &ManualClock,
+        time_passed: Duration,
+    ) -> Result<MainPhaseAction, PlayerError> {
+        let state = state();
+        let no_cards = vec![];
+        let basic_actions = vec![BasicAction::Focus];
+        let costs = vec![BasicActionCost::Vigor];
+        let decision = player.main_phase_action(&state, &no_cards, &basic_actions, &costs);
+        let time = async {
+            // Let the decision start waiting first.
+            tokio::task::yield_now().await;
+            clock.advance(time_passed);
+        };
+
+        tokio::join!(decision, time).0
+    }
+
+    #[tokio::test]
+    async fn overtime_is_charged_to_the_bank() {
+        let clock = ManualClock::new();
+        let slow_player = SlowPlayer {
+            clock: clock.clone(),
+            delay: Duration::from_secs(12),
+        };
+        let mut player = TimedPlayer::new(Box::new(slow_player), TIME_CONTROL, clock.clone());
+
+        let action = decide_while(&mut player, &clock, Duration::from_secs(12)).await;
+
+        assert!(matches!(
+            action,
+            Ok(MainPhaseAction::PlayBasicAction { .. })
+        ));
+        assert_eq!(player.time_left().bank, Some(Duration::from_secs(3)));
+    }
+
+    #[tokio::test]
+    async fn timed_out_decision_takes_the_default_action() {
+        let clock = ManualClock::new();
+        let slow_player = SlowPlayer {
+            clock: clock.clone(),
+            delay: Duration::from_secs(60),
+        };
+        let mut player = TimedPlayer::new(Box::new(slow_player), TIME_CONTROL, clock.clone());
+
+        let action = decide_while(&mut player, &clock, Duration::from_secs(15)).await;
+
+        assert_eq!(action, Ok(MainPhaseAction::EndMainPhase));
+        assert_eq!(player.time_left().bank, Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn timed_out_decision_forfeits() {
+        let clock = ManualClock::new();
+        let slow_player = SlowPlayer {
+            clock: clock.clone(),
+            delay: Duration::from_secs(60),
+        };
+        let time_control = TimeControl {
+            on_timeout: TimeoutPolicy::Forfeit,
+            ..TIME_CONTROL
+        };
+        let mut player = TimedPlayer::new(Box::new(slow_player), time_control, clock.clone());
+
+        let action = decide_while(&mut player, &clock, Duration::from_secs(15)).await;
+
+        assert_eq!(action, Err(PlayerError::TimedOut));
+    }
+}
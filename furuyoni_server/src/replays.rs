@@ -0,0 +1,64 @@
+use crate::game::RecordedGame;
+use furuyoni_lib::net::frames::PlayerIdentity;
+use furuyoni_lib::replays::{DecisionRecord, Replay, ReplayError, REPLAY_FORMAT_VERSION};
+use furuyoni_lib::rules::states::PlayersData;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub(crate) enum SaveReplayError {
+    #[error("Failed to serialize the replay: {0}")]
+    SerializationFailed(#[from] ReplayError),
+    #[error("Failed to write the replay file: {0}")]
+    WriteFailed(#[from] std::io::Error),
+}
+
+/// Make the replay of a finished game.
+pub(crate) fn replay_of(
+    recorded: RecordedGame,
+    players: PlayersData<PlayerIdentity>,
+    decisions: Vec<DecisionRecord>,
+) -> Replay {
+    Replay {
+        version: REPLAY_FORMAT_VERSION,
+        players,
+        deck_lists: recorded.deck_lists,
+        seed: recorded.seed,
+        initial_game_state: recorded.initial_game_state,
+        events: recorded.recorded_events,
+        decisions,
+    }
+}
+
+/// Keeps a replay file for every finished game in a directory.
+pub(crate) struct ReplayArchive {
+    directory: PathBuf,
+}
+
+impl ReplayArchive {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// Write the replay into a new file, and return the path of the file.
+    pub async fn save(&self, game_id: u32, replay: &Replay) -> Result<PathBuf, SaveReplayError> {
+        let serialized = replay.to_json()?;
+
+        // The game ids start over when the server restarts, so the time keeps the names unique.
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = self
+            .directory
+            .join(format!("{}-{}.replay.json", timestamp, game_id));
+
+        tokio::fs::create_dir_all(&self.directory).await?;
+        tokio::fs::write(&path, serialized).await?;
+
+        Ok(path)
+    }
+}
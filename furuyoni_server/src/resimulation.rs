@@ -0,0 +1,202 @@
+use crate::game::{create_game, CreateGameError, GameError};
+use crate::players::{RequestMismatch, ScriptedPlayer};
+use furuyoni_lib::replays::{Replay, ReplayError};
+use furuyoni_lib::rules::events::GameEvent;
+use furuyoni_lib::rules::PlayerPos;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+#[derive(Error, Debug)]
+pub(crate) enum ResimulationError {
+    #[error("Failed to create the game: {0}")]
+    CreateGameFailed(#[from] CreateGameError),
+    #[error("The game has ended with an error: {0}")]
+    GameFailed(#[from] GameError),
+}
+
+/// Where a re-simulated game has first differed from its recording.
+#[derive(Error, Debug)]
+pub(crate) enum Divergence {
+    /// `None` if the game has ended before the event.
+    #[error("Event {index} differs. Recorded: {recorded:?}, re-simulated: {resimulated:?}")]
+    Event {
+        index: usize,
+        recorded: Option<GameEvent>,
+        resimulated: Option<GameEvent>,
+    },
+    /// A player has been requested a different decision while the events were still the same.
+    #[error(
+        "The request to {:?} after event {} differs. Recorded: {:?}, re-simulated: {:?}",
+        .0.player, .0.event_index, .0.recorded, .0.resimulated
+    )]
+    Request(Box<RequestMismatch>),
+}
+
+/// Play the recorded game again from its seed with the recorded decisions, and return where it
+/// has first differed from the recording, if anywhere.
+pub(crate) async fn resimulate(replay: &Replay) -> Result<Option<Divergence>, ResimulationError> {
+    let recorded_outcome = match replay.events.last() {
+        Some(GameEvent::GameEnd(outcome)) => Some(*outcome),
+        _ => None,
+    };
+    let mismatch = Arc::new(Mutex::new(None));
+
+    // The players surrender through this channel, as the game doesn't exist yet.
+    let (surrender_tx, mut surrender_rx) = mpsc::channel(2);
+    let [p1, p2] = [PlayerPos::P1, PlayerPos::P2].map(|pos| {
+        ScriptedPlayer::new(
+            pos,
+            &replay.decisions,
+            recorded_outcome,
+            surrender_tx.clone(),
+            mismatch.clone(),
+        )
+    });
+
+    let (game, recorder) = create_game(
+        Box::new(p1),
+        Box::new(p2),
+        replay.deck_lists.clone(),
+        replay.seed,
+    )?;
+
+    let game_surrender_tx = game.surrender_sender();
+    let surrender_task = tokio::spawn(async move {
+        while let Some(player) = surrender_rx.recv().await {
+            let _ = game_surrender_tx.send(player).await;
+        }
+    });
+
+    let result = game.run().await;
+
+    surrender_task.abort();
+    let _ = surrender_task.await;
+    result?;
+
+    let resimulated = Arc::into_inner(recorder)
+        .unwrap()
+        .into_recorded_game()
+        .recorded_events;
+
+    let length = replay.events.len().max(resimulated.len());
+    let diverged_at = (0..length).find(|&index| replay.events.get(index) != resimulated.get(index));
+    let request_mismatch = mismatch.lock().unwrap().take();
+
+    // A request is made after the events before it, and before the event at its index.
+    let divergence = match (diverged_at, request_mismatch) {
+        (Some(index), Some(mismatch)) if mismatch.event_index <= index => {
+            Some(Divergence::Request(Box::new(mismatch)))
+        }
+        (Some(index), _) => Some(Divergence::Event {
+            index,
+            recorded: replay.events.get(index).copied(),
+            resimulated: resimulated.get(index).copied(),
+        }),
+        (None, mismatch) => mismatch.map(|mismatch| Divergence::Request(Box::new(mismatch))),
+    };
+    Ok(divergence)
+}
+
+/// Re-simulate every replay in the directory and print the result of each. Returns whether every
+/// replay has been reproduced exactly.
+pub(crate) async fn verify_replays(directory: impl AsRef<Path>) -> Result<bool, ReplayError> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.to_string_lossy().ends_with(".replay.json"))
+        .collect();
+    paths.sort();
+
+    let mut all_reproduced = true;
+    for path in paths {
+        let result = match Replay::load(&path) {
+            Ok(replay) => resimulate(&replay).await,
+            Err(e) => {
+                println!("{}: {}", path.display(), e);
+                all_reproduced = false;
+                continue;
+            }
+        };
+
+        match result {
+            Ok(None) => println!("{}: reproduced", path.display()),
+            Ok(Some(divergence)) => {
+                println!("{}: diverged. {}", path.display(), divergence);
+                all_reproduced = false;
+            }
+            Err(e) => {
+                println!("{}: {}", path.display(), e);
+                all_reproduced = false;
+            }
+        }
+    }
+
+    Ok(all_reproduced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TokioClock;
+    use crate::game::default_deck_list;
+    use crate::players::{DecisionLog, IdlePlayer, RecordingPlayer};
+    use crate::replays::replay_of;
+    use furuyoni_lib::net::frames::{PlayerId, PlayerIdentity};
+    use furuyoni_lib::rules::events::UpdateGameState;
+    use furuyoni_lib::rules::states::{Phase, PlayersData};
+
+    /// Play a game between idle players, and return its replay as read back from its file.
+    async fn recorded_replay() -> Replay {
+        let decisions = Arc::new(DecisionLog::new());
+        let [p1, p2] = [PlayerPos::P1, PlayerPos::P2].map(|pos| {
+            RecordingPlayer::new(Box::new(IdlePlayer {}), pos, decisions.clone(), TokioClock)
+        });
+        let deck_lists = PlayersData::new(default_deck_list(), default_deck_list());
+
+        let (game, recorder) = create_game(Box::new(p1), Box::new(p2), deck_lists, 42).unwrap();
+        game.run().await.unwrap();
+
+        let recorded = Arc::into_inner(recorder).unwrap().into_recorded_game();
+        let identity = |id| PlayerIdentity {
+            id: PlayerId(id),
+            display_name: String::new(),
+        };
+        let replay = replay_of(
+            recorded,
+            PlayersData::new(identity(0), identity(1)),
+            decisions.take(),
+        );
+
+        Replay::from_json(&replay.to_json().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn recorded_game_is_reproduced() {
+        let replay = recorded_replay().await;
+
+        assert!(matches!(replay.events.last(), Some(GameEvent::GameEnd(_))));
+        assert!(matches!(resimulate(&replay).await, Ok(None)));
+    }
+
+    #[tokio::test]
+    async fn mutated_event_is_reported_where_it_is() {
+        let mut replay = recorded_replay().await;
+        // Mutate the last main phase, so that most of the game has to be reproduced before it.
+        let main_phase = GameEvent::StateUpdated(UpdateGameState::SetPhase(Phase::Main));
+        let mutated = replay
+            .events
+            .iter()
+            .rposition(|event| *event == main_phase)
+            .unwrap();
+        assert!(mutated > replay.events.len() / 2);
+        replay.events[mutated] = GameEvent::StateUpdated(UpdateGameState::SetPhase(Phase::End));
+
+        let divergence = resimulate(&replay).await.unwrap();
+
+        assert!(matches!(
+            divergence,
+            Some(Divergence::Event { index, .. }) if index == mutated
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+use furuyoni_lib::net::frames::{
+    Credentials, PlayerId, PlayerIdentity, PlayerToLobbyHandshake, SessionId, PROTOCOL_VERSION,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub(crate) enum HandshakeError {
+    #[error("Protocol version mismatch: the server is on {PROTOCOL_VERSION}, the client is on {0}.")]
+    ProtocolVersionMismatch(u32),
+    #[error("The session to resume doesn't exist.")]
+    SessionNotFound,
+}
+
+/// The sessions of the connected clients, and the identities they play as.
+pub(crate) struct SessionRegistry {
+    inner: Mutex<RegistryInner>,
+}
+
+struct RegistryInner {
+    next_player_id: u64,
+    sessions: HashMap<SessionId, PlayerIdentity>,
+    /// The identities that can be claimed again with their token.
+    tokens: HashMap<String, PlayerIdentity>,
+}
+
+impl SessionRegistry {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(RegistryInner {
+                next_player_id: 0,
+                sessions: HashMap::new(),
+                tokens: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Start a session for the client with the identity it has asked for, or resume one.
+    pub fn start_session(
+        &self,
+        handshake: PlayerToLobbyHandshake,
+    ) -> Result<(SessionId, PlayerIdentity), HandshakeError> {
+        if handshake.protocol_version != PROTOCOL_VERSION {
+            return Err(HandshakeError::ProtocolVersionMismatch(
+                handshake.protocol_version,
+            ));
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+
+        if let Credentials::Resume(session_id) = handshake.credentials {
+            let identity = inner
+                .sessions
+                .get(&session_id)
+                .cloned()
+                .ok_or(HandshakeError::SessionNotFound)?;
+            return Ok((session_id, identity));
+        }
+
+        let identity = match handshake.credentials {
+            Credentials::DisplayName(display_name) => inner.new_identity(display_name),
+            // Todo: verify the token with an account service.
+            Credentials::Token(token) => match inner.tokens.get(&token) {
+                Some(identity) => identity.clone(),
+                None => {
+                    let identity = inner.new_identity(String::new());
+                    inner.tokens.insert(token, identity.clone());
+                    identity
+                }
+            },
+            Credentials::Resume(_) => unreachable!(),
+        };
+
+        let session_id = loop {
+            let session_id = SessionId(rand::random());
+            if !inner.sessions.contains_key(&session_id) {
+                break session_id;
+            }
+        };
+        inner.sessions.insert(session_id, identity.clone());
+
+        Ok((session_id, identity))
+    }
+
+    pub fn end_session(&self, session_id: SessionId) {
+        self.inner.lock().unwrap().sessions.remove(&session_id);
+    }
+}
+
+impl RegistryInner {
+    /// Create an identity. An empty display name is replaced with one made of the id.
+    fn new_identity(&mut self, display_name: String) -> PlayerIdentity {
+        let id = PlayerId(self.next_player_id);
+        self.next_player_id += 1;
+
+        let display_name = if display_name.is_empty() {
+            format!("Player {}", id.0)
+        } else {
+            display_name
+        };
+
+        PlayerIdentity { id, display_name }
+    }
+}
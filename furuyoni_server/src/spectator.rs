@@ -0,0 +1,116 @@
+use crate::game_watcher::{GameObserver, NotifyFailedError};
+use crate::main_channels::GameRequester;
+use furuyoni_lib::net::frames::GameToPlayerRequest;
+use furuyoni_lib::rules::events::GameEvent;
+use furuyoni_lib::rules::states::StateView;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+
+/// Streams a game to a client watching it. The client is never asked for a decision.
+pub(crate) struct Spectator {
+    tx: mpsc::UnboundedSender<(Instant, GameToPlayerRequest)>,
+}
+
+impl Spectator {
+    /// The client sees the game `delay` behind the players. The requester is sent back through
+    /// `returned_tx` once the client has seen the whole game.
+    pub fn new(
+        requester: GameRequester,
+        delay: Duration,
+        returned_tx: oneshot::Sender<GameRequester>,
+    ) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let requester = forward_delayed(rx, requester, delay).await;
+
+            // The client may have left.
+            let _ = returned_tx.send(requester);
+        });
+
+        Self { tx }
+    }
+
+    fn notify(&self, notification: GameToPlayerRequest) -> Result<(), NotifyFailedError> {
+        self.tx
+            .send((Instant::now(), notification))
+            .map_err(|_| NotifyFailedError)
+    }
+}
+
+/// Send each notification to the client once the delay has passed. Ends when the client is gone
+/// or the spectator is dropped, and returns the requester.
+async fn forward_delayed(
+    mut rx: mpsc::UnboundedReceiver<(Instant, GameToPlayerRequest)>,
+    requester: GameRequester,
+    delay: Duration,
+) -> GameRequester {
+    while let Some((notified_at, notification)) = rx.recv().await {
+        tokio::time::sleep_until(notified_at + delay).await;
+
+        if requester.send(notification).is_err() {
+            break;
+        }
+    }
+
+    requester
+}
+
+impl GameObserver for Spectator {
+    fn initialize_state(&mut self, state: &StateView) -> Result<(), NotifyFailedError> {
+        self.notify(GameToPlayerRequest::InitializeGameState(state.clone()))
+    }
+
+    fn notify_event(&mut self, event: GameEvent) -> Result<(), NotifyFailedError> {
+        self.notify(GameToPlayerRequest::NotifyEvent(event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{create_game, default_deck_list};
+    use crate::players::IdlePlayer;
+    use furuyoni_lib::net::message_channel::MessageChannel;
+    use furuyoni_lib::rules::states::PlayersData;
+    use furuyoni_lib::rules::ObservePosition;
+
+    #[tokio::test]
+    async fn spectator_sees_the_end_of_the_game() {
+        // Large enough for every notification of the game, as the requester can't wait for room.
+        let (request_tx, mut request_rx) = mpsc::channel(4096);
+        let (_response_tx, response_rx) = mpsc::channel(1);
+        let (returned_tx, returned_rx) = oneshot::channel();
+        let spectator = Spectator::new(
+            MessageChannel::new(request_tx, response_rx),
+            Duration::ZERO,
+            returned_tx,
+        );
+
+        let deck_lists = PlayersData::new(default_deck_list(), default_deck_list());
+        let (game, recorder) = create_game(
+            Box::new(IdlePlayer {}),
+            Box::new(IdlePlayer {}),
+            deck_lists,
+            42,
+        )
+        .unwrap();
+        recorder
+            .add_observer(ObservePosition::ByStander, spectator)
+            .unwrap();
+        game.run().await.unwrap();
+
+        // The spectator gives the requester back once it has been dropped along with the recorder.
+        drop(recorder);
+        drop(returned_rx.await.unwrap());
+
+        let mut notifications = vec![];
+        while let Some(notification) = request_rx.recv().await {
+            notifications.push(notification);
+        }
+        assert!(matches!(
+            notifications.last(),
+            Some(GameToPlayerRequest::NotifyEvent(GameEvent::GameEnd(_)))
+        ));
+    }
+}